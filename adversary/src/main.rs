@@ -0,0 +1,215 @@
+use problems::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use search::{evaluate, Method};
+use serde_json::{json, Value};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::process::ExitCode;
+
+mod opt;
+mod problems;
+mod search;
+
+const TRIALS: usize = 16;
+
+fn usage() -> String {
+    format!(
+        "usage: adversary search <problem> <algorithm> [params] [--method local|anneal|genetic]
+                        [--length L] [--iterations N] [--top K] [--trials T] [--seed S] [--out FILE]
+       adversary replay <FILE>
+problems:
+{}",
+        PROBLEMS
+    )
+}
+
+// what to do with a problem once its request type is known
+trait Task {
+    fn run<P: Problem>(&self, problem: P) -> Result<bool, String>;
+}
+
+fn dispatch<T: Task>(problem: &str, algorithm: &str, params: &[String], trials: usize, task: &T) -> Result<bool, String> {
+    match problem {
+        "list_update" => task.run(ListUpdate::new(algorithm, params, trials)?),
+        "paging" => task.run(Paging::new(algorithm, params, trials)?),
+        "bin_packing" => task.run(BinPacking::new(algorithm, trials)?),
+        "page_migration" => task.run(PageMigrating::new(algorithm, params, trials)?),
+        "page_allocation" => task.run(PageAllocating::new(algorithm, params)?),
+        _ => Err(format!("unknown problem: {}", problem)),
+    }
+}
+
+struct Search {
+    problem: String,
+    algorithm: String,
+    params: Vec<String>,
+    method: Method,
+    length: usize,
+    iterations: usize,
+    top: usize,
+    trials: usize,
+    seed: u64,
+    out: String,
+}
+
+impl Task for Search {
+    fn run<P: Problem>(&self, problem: P) -> Result<bool, String> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let found = search::search(&problem, self.method, self.length, self.iterations, self.top, &mut rng);
+        let found = search::hold_out(&problem, &found, &mut rng);
+
+        let file = File::create(&self.out).map_err(|e| format!("cannot create {}: {}", self.out, e))?;
+        let mut writer = BufWriter::new(file);
+        for (rank, (found, searched)) in found.iter().enumerate() {
+            let requests = found.requests.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+            // randomized ratios are reported on held-out seeds, next to the search's estimate
+            let during = if problem.trials() > 1 { format!(", {:.4} in the search", searched) } else { String::new() };
+            println!(
+                "#{} ratio {:.4} (online {}, optimum {}{}): {}",
+                rank + 1, found.ratio, found.online, found.optimum, during, requests.join(" ")
+            );
+            let trace = json!({
+                "problem": self.problem,
+                "algorithm": self.algorithm,
+                "params": self.params,
+                "trials": self.trials,
+                "method": self.method.to_string(),
+                "seed": self.seed,
+                "seeds": found.seeds,
+                "rank": rank + 1,
+                "online": found.online,
+                "optimum": found.optimum,
+                "ratio": found.ratio,
+                "search_ratio": searched,
+                "requests": requests,
+            });
+            writeln!(writer, "{}", trace).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        println!("wrote {} traces to {}", found.len(), self.out);
+        Ok(true)
+    }
+}
+
+fn search_command(args: &[String]) -> Result<bool, String> {
+    let mut words = Vec::new();
+    let mut method = Method::Anneal;
+    let mut length = 12;
+    let mut iterations = 20000;
+    let mut top = 5;
+    let mut trials = TRIALS;
+    let mut seed = rand::random();
+    let mut out = "worst.jsonl".to_string();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(usage);
+        let positive = |v: &String| v.parse().ok().filter(|&n: &usize| n > 0).ok_or_else(|| format!("{} expects a positive number, got {}", arg, v));
+        match arg.as_str() {
+            "--method" => method = Method::parse(value()?)?,
+            "--length" => length = positive(value()?)?,
+            "--iterations" => iterations = positive(value()?)?,
+            "--top" => top = positive(value()?)?,
+            "--trials" => trials = positive(value()?)?,
+            "--seed" => {
+                let v = value()?;
+                seed = v.parse().map_err(|_| format!("--seed expects a number, got {}", v))?;
+            }
+            "--out" => out = value()?.clone(),
+            _ => words.push(arg.clone()),
+        }
+    }
+    let [problem, algorithm, params @ ..] = words.as_slice() else {
+        return Err(usage());
+    };
+
+    let search = Search {
+        problem: problem.clone(),
+        algorithm: algorithm.clone(),
+        params: params.to_vec(),
+        method,
+        length,
+        iterations,
+        top,
+        trials,
+        seed,
+        out,
+    };
+    println!("{} {} {}, {} search over {} requests, seed {}", problem, algorithm, params.join(" "), method, length, seed);
+    dispatch(problem, algorithm, params, trials, &search)
+}
+
+struct Replay {
+    trace: Value,
+}
+
+impl Task for Replay {
+    // recomputes both costs and reports whether they match the recorded ones
+    fn run<P: Problem>(&self, problem: P) -> Result<bool, String> {
+        let requests = self.trace["requests"]
+            .as_array()
+            .ok_or("trace without requests")?
+            .iter()
+            .map(|r| problem.parse(r.as_str().unwrap_or_default()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let seeds = self.trace["seeds"]
+            .as_array()
+            .ok_or("trace without seeds")?
+            .iter()
+            .map(|s| s.as_u64().ok_or("trace with an invalid seed"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let found = evaluate(&problem, requests, seeds);
+
+        let matches = self.trace["online"].as_f64() == Some(found.online)
+            && self.trace["optimum"].as_u64() == Some(found.optimum as u64);
+        println!(
+            "#{} ratio {:.4} (online {}, optimum {}){}",
+            self.trace["rank"], found.ratio, found.online, found.optimum,
+            if matches { "" } else { ", differs from the recorded trace" }
+        );
+        Ok(matches)
+    }
+}
+
+fn replay_command(args: &[String]) -> Result<bool, String> {
+    let [path] = args else {
+        return Err(usage());
+    };
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+
+    let mut all = true;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let trace: Value = serde_json::from_str(line).map_err(|e| format!("invalid trace: {}", e))?;
+        let text = |key: &str| trace[key].as_str().map(|s| s.to_string()).ok_or(format!("trace without {}", key));
+        let (problem, algorithm) = (text("problem")?, text("algorithm")?);
+        let params = trace["params"]
+            .as_array()
+            .map(|params| params.iter().filter_map(|p| p.as_str().map(|s| s.to_string())).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let trials = trace["trials"].as_u64().unwrap_or(TRIALS as u64) as usize;
+        all &= dispatch(&problem, &algorithm, &params, trials, &Replay { trace })?;
+    }
+    Ok(all)
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("search") => search_command(&args[1..]),
+        Some("replay") => replay_command(&args[1..]),
+        _ => Err(usage()),
+    };
+
+    // 1 signals a replayed trace that no longer matches, 2 a failure to run at all
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
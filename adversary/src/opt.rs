@@ -0,0 +1,280 @@
+use metricgraph::MetricGraph;
+use online::allocation::Access;
+use std::collections::{HashMap, HashSet};
+
+// all orders of the list 1..=m, with the orders one adjacent exchange away
+pub struct Permutations {
+    perms: Vec<Vec<usize>>,
+    index: HashMap<Vec<usize>, usize>,
+    exchanges: Vec<Vec<usize>>,
+}
+
+impl Permutations {
+    pub fn new(m: usize) -> Self {
+        let mut perms = vec![Vec::new()];
+        for e in 1..=m {
+            perms = perms
+                .into_iter()
+                .flat_map(|p: Vec<usize>| {
+                    (0..=p.len()).map(move |i| {
+                        let mut q = p.clone();
+                        q.insert(i, e);
+                        q
+                    })
+                })
+                .collect();
+        }
+        let index = perms.iter().enumerate().map(|(i, p)| (p.clone(), i)).collect::<HashMap<_, _>>();
+        let exchanges = perms
+            .iter()
+            .map(|p| {
+                (1..m)
+                    .map(|i| {
+                        let mut q = p.clone();
+                        q.swap(i - 1, i);
+                        index[&q]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { perms, index, exchanges }
+    }
+
+    // the cheapest way to serve `requests` starting from the list 1..=m, paying
+    // the position of each access and 1 per paid exchange; the accessed element
+    // may move forward for free afterwards
+    pub fn list_update(&self, requests: &[usize]) -> usize {
+        let identity = (1..=self.perms[0].len()).collect::<Vec<_>>();
+        let mut cost = vec![usize::MAX; self.perms.len()];
+        cost[self.index[&identity]] = 0;
+
+        for &x in requests {
+            // paid exchanges are shortest paths over adjacent transpositions
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for p in 0..self.perms.len() {
+                    if cost[p] == usize::MAX {
+                        continue;
+                    }
+                    for &q in &self.exchanges[p] {
+                        if cost[p] + 1 < cost[q] {
+                            cost[q] = cost[p] + 1;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            for (p, perm) in self.perms.iter().enumerate() {
+                if cost[p] != usize::MAX {
+                    cost[p] += perm.iter().position(|&e| e == x).unwrap() + 1;
+                }
+            }
+
+            for p in 0..self.perms.len() {
+                let at = self.perms[p].iter().position(|&e| e == x).unwrap();
+                for to in 0..at {
+                    let mut q = self.perms[p].clone();
+                    q.remove(at);
+                    q.insert(to, x);
+                    let q = self.index[&q];
+                    cost[q] = cost[q].min(cost[p]);
+                }
+            }
+        }
+
+        cost.into_iter().min().unwrap()
+    }
+}
+
+// Belady's rule: on a miss with a full cache evict the page requested
+// furthest in the future
+pub fn paging(k: usize, requests: &[usize]) -> usize {
+    let mut cache = HashSet::new();
+    let mut misses = 0;
+    for (i, page) in requests.iter().enumerate() {
+        if cache.contains(page) {
+            continue;
+        }
+        misses += 1;
+        if cache.len() == k {
+            let next_use = |p: &usize| requests[i + 1..].iter().position(|r| r == p).unwrap_or(usize::MAX);
+            let victim = *cache.iter().max_by_key(|p| next_use(p)).unwrap();
+            cache.remove(&victim);
+        }
+        cache.insert(*page);
+    }
+    misses
+}
+
+// the fewest bins of size `capacity` holding the items, by branch and bound
+pub fn bin_packing(capacity: usize, items: &[usize]) -> usize {
+    fn search(capacity: usize, items: &[usize], remaining: usize, bins: &mut Vec<usize>, best: &mut usize) {
+        let free = bins.iter().map(|b| capacity - b).sum::<usize>();
+        let lower = bins.len() + remaining.saturating_sub(free).div_ceil(capacity);
+        if lower >= *best {
+            return;
+        }
+        let Some((&item, rest)) = items.split_first() else {
+            *best = bins.len();
+            return;
+        };
+
+        let mut tried = HashSet::new();
+        for i in 0..bins.len() {
+            if bins[i] + item <= capacity && tried.insert(bins[i]) {
+                bins[i] += item;
+                search(capacity, rest, remaining - item, bins, best);
+                bins[i] -= item;
+            }
+        }
+        bins.push(item);
+        search(capacity, rest, remaining - item, bins, best);
+        bins.pop();
+    }
+
+    let mut items = items.to_vec();
+    items.sort_by(|a, b| b.cmp(a));
+    let mut best = items.len() + 1;
+    search(capacity, &items, items.iter().sum(), &mut Vec::new(), &mut best);
+    best.min(items.len())
+}
+
+// dynamic programming over the page's node, starting at node 0: each read
+// costs the distance to the page, after which moving it costs d per unit
+pub fn page_migration(graph: &dyn MetricGraph, d: usize, requests: &[usize]) -> usize {
+    let n = graph.size();
+    let distance = |x, y| graph.distance(x, y).unwrap();
+    let mut cost = vec![usize::MAX; n];
+    cost[0] = 0;
+
+    for &source in requests {
+        for (v, c) in cost.iter_mut().enumerate() {
+            if *c != usize::MAX {
+                *c += distance(v, source);
+            }
+        }
+        cost = (0..n)
+            .map(|w| (0..n).filter(|&v| cost[v] != usize::MAX).map(|v| cost[v] + d * distance(v, w)).min().unwrap())
+            .collect();
+    }
+
+    cost.into_iter().min().unwrap()
+}
+
+// dynamic programming over the set of replicas on a uniform metric, starting
+// with one at node 0: a remote read costs 1, a write 1 per other replica,
+// a new replica d and dropping one nothing
+pub fn page_allocation(nodes: usize, d: usize, requests: &[Access]) -> usize {
+    let sets = 1usize << nodes;
+    let mut cost = vec![usize::MAX; sets];
+    cost[1] = 0;
+
+    for &access in requests {
+        for bit in (0..nodes).map(|i| 1 << i) {
+            for s in 0..sets {
+                if s & bit == 0 {
+                    cost[s] = cost[s].min(cost[s | bit]);
+                }
+            }
+        }
+        for bit in (0..nodes).map(|i| 1 << i) {
+            for s in 0..sets {
+                if s & bit == 0 && cost[s] != usize::MAX {
+                    cost[s | bit] = cost[s | bit].min(cost[s] + d);
+                }
+            }
+        }
+        cost[0] = usize::MAX;
+
+        for (s, c) in cost.iter_mut().enumerate() {
+            if *c == usize::MAX {
+                continue;
+            }
+            let local = (s >> access.node()) & 1;
+            *c += match access {
+                Access::Read(_) => 1 - local,
+                Access::Write(_) => s.count_ones() as usize - local,
+            };
+        }
+    }
+
+    cost.into_iter().min().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metricgraph::Torus;
+    use online::allocation::Access::{Read, Write};
+
+    #[test]
+    fn list_update_pays_the_cheapest_reordering() {
+        let permutations = Permutations::new(3);
+        assert_eq!(permutations.list_update(&[]), 0);
+        assert_eq!(permutations.list_update(&[1, 1, 1]), 3);
+        // 3 then a free move to the front, or two paid exchanges first
+        assert_eq!(permutations.list_update(&[3]), 3);
+        assert_eq!(permutations.list_update(&[3, 3, 3, 3]), 6);
+        // alternating between the first two, any reordering costs more
+        assert_eq!(permutations.list_update(&[2, 1, 2, 1]), 6);
+        // 2 moves to the front and 3 behind it for free, giving 2 3 1
+        assert_eq!(permutations.list_update(&[2, 3, 2, 3, 2, 3]), 2 + 3 + 1 + 2 + 1 + 2);
+    }
+
+    #[test]
+    fn paging_evicts_the_page_needed_last() {
+        assert_eq!(paging(2, &[]), 0);
+        // 3 evicts 2, then 2 evicts 1: misses on 1 2 3 2
+        assert_eq!(paging(2, &[1, 2, 3, 1, 2, 3]), 4);
+        // the textbook string with three frames
+        assert_eq!(paging(3, &[1, 2, 3, 4, 1, 2, 5, 1, 2, 3, 4, 5]), 7);
+        assert_eq!(paging(3, &[1, 2, 3, 1, 2, 3]), 3);
+    }
+
+    #[test]
+    fn bin_packing_finds_the_fewest_bins() {
+        assert_eq!(bin_packing(10, &[]), 0);
+        assert_eq!(bin_packing(10, &[5, 5, 5]), 2);
+        assert_eq!(bin_packing(10, &[7, 7, 7]), 3);
+        assert_eq!(bin_packing(10, &[6, 4, 6, 4]), 2);
+        // first fit decreasing needs 3 bins: 5 4, 3 3 3, 2; the optimum is 5 3 2, 4 3 3
+        assert_eq!(bin_packing(10, &[2, 3, 5, 3, 4, 3]), 2);
+    }
+
+    #[test]
+    fn page_migration_moves_when_reads_repeat() {
+        // a cycle of 4 nodes, node 2 at distance 2 from the start
+        let cycle = Torus::<1>::new(4);
+        assert_eq!(page_migration(&cycle, 1, &[]), 0);
+        assert_eq!(page_migration(&cycle, 1, &[0, 0]), 0);
+        assert_eq!(page_migration(&cycle, 1, &[2]), 2);
+        // staying costs 2 + 2, moving after the first read 2 + 2 + 0
+        assert_eq!(page_migration(&cycle, 1, &[2, 2]), 4);
+        assert_eq!(page_migration(&cycle, 1, &[2, 2, 2]), 4);
+        // moving costs d per unit, so with d = 3 staying is cheaper
+        assert_eq!(page_migration(&cycle, 3, &[2, 2, 2]), 6);
+        assert_eq!(page_migration(&cycle, 3, &[2, 2, 2, 2, 2]), 2 + 6);
+        // alternating between 1 and 3 stays at 0
+        assert_eq!(page_migration(&cycle, 1, &[1, 3, 1, 3]), 4);
+    }
+
+    #[test]
+    fn page_allocation_replicates_for_repeated_reads() {
+        assert_eq!(page_allocation(2, 2, &[]), 0);
+        assert_eq!(page_allocation(2, 2, &[Write(0)]), 0);
+        assert_eq!(page_allocation(2, 2, &[Read(1)]), 1);
+        // a replica at 1 costs d = 2 and saves every later remote read
+        assert_eq!(page_allocation(2, 2, &[Read(1), Read(1)]), 2);
+        assert_eq!(page_allocation(2, 2, &[Read(1), Read(1), Read(1)]), 2);
+        // the replica at 1 is dropped for free before writes to 0
+        assert_eq!(page_allocation(2, 2, &[Read(1), Read(1), Read(1), Write(0), Write(0)]), 2);
+        // a write to 0 with replicas at both nodes updates the one at 1
+        assert_eq!(page_allocation(2, 2, &[Read(1), Read(1), Write(0), Read(1), Read(1)]), 3);
+        // migrating to 1 costs d and makes its writes local
+        assert_eq!(page_allocation(2, 2, &[Write(1), Write(1), Write(1)]), 2);
+        assert_eq!(page_allocation(3, 1, &[Read(1), Read(2), Read(1), Read(2)]), 2);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::opt::{self, Permutations};
+use metricgraph::*;
+use online::allocation::*;
+use online::bin_packing::*;
+use online::dynlist::*;
+use online::events::{Event, Observer};
+use online::migration::*;
+use online::paging::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::fmt;
+
+// item sizes are multiples of 1/GRAIN, so that sums stay exact in f64
+pub const GRAIN: usize = 64;
+
+pub const PROBLEMS: &str = "  list_update Simple|TP|MTF|FC|TS|BIT|COMB <elements>
+  paging FIFO|FWF|LRU|LFU|RAND|RMA <k> <pages>
+  bin_packing NF|RF|FF|BF|WF
+  page_migration MoveToMin|CoinFlip torus3d|hypercube <d>
+  page_allocation Count <nodes> <d>";
+
+// an online problem with an exactly solvable offline optimum on short inputs
+pub trait Problem {
+    type Request: Clone + fmt::Display;
+
+    fn random(&self, rng: &mut StdRng) -> Self::Request;
+    fn parse(&self, request: &str) -> Result<Self::Request, String>;
+    // how many coin seeds an evaluation averages over, 1 for deterministic algorithms
+    fn trials(&self) -> usize {
+        1
+    }
+    // the online cost, averaged over one run per seed for randomized algorithms
+    fn online(&self, requests: &[Self::Request], seeds: &[u64]) -> f64;
+    fn optimum(&self, requests: &[Self::Request]) -> usize;
+}
+
+// builders taking the algorithm's seed, which only randomized ones use
+type MakeList = fn(u64) -> Box<dyn Dynlist<usize>>;
+type MakeCache = fn(usize, u64) -> Box<dyn Cacher<usize>>;
+type MakePacker = fn(u64) -> Box<dyn Packer<f64>>;
+type MakeMigration = fn(Box<dyn MetricGraph>, usize, u64) -> Box<dyn PageMigration>;
+
+fn number<T: std::str::FromStr>(text: &str, what: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("expected {}, got {}", what, text))
+}
+
+fn param(params: &[String], i: usize, what: &str) -> Result<usize, String> {
+    let value = params.get(i).ok_or_else(|| format!("missing {}", what))?;
+    number::<usize>(value, what).and_then(|v| if v > 0 { Ok(v) } else { Err(format!("{} must be positive", what)) })
+}
+
+fn mean(seeds: &[u64], cost: impl Fn(u64) -> usize) -> f64 {
+    seeds.iter().map(|&seed| cost(seed)).sum::<usize>() as f64 / seeds.len() as f64
+}
+
+pub struct ListUpdate {
+    make: MakeList,
+    elements: usize,
+    permutations: Permutations,
+    trials: usize,
+}
+
+impl ListUpdate {
+    pub fn new(algorithm: &str, params: &[String], trials: usize) -> Result<Self, String> {
+        let (make, randomized): (MakeList, bool) = match algorithm {
+            "Simple" => (|_| Box::new(SimpleList::new()), false),
+            "TP" => (|_| Box::new(TPList::new()), false),
+            "MTF" => (|_| Box::new(MTFList::new()), false),
+            "FC" => (|_| Box::new(FCList::new()), false),
+            "TS" => (|_| Box::new(TSList::new()), false),
+            "BIT" => (|seed| Box::new(BITList::with_seed(seed)), true),
+            "COMB" => (|seed| Box::new(COMBList::with_seed(seed)), true),
+            _ => return Err(format!("unknown list: {}", algorithm)),
+        };
+        let elements = param(params, 0, "a number of elements")?;
+        if elements > 7 {
+            return Err("the optimum is computed over all orders, use at most 7 elements".to_string());
+        }
+        Ok(Self { make, elements, permutations: Permutations::new(elements), trials: if randomized { trials } else { 1 } })
+    }
+}
+
+impl Problem for ListUpdate {
+    type Request = usize;
+
+    fn random(&self, rng: &mut StdRng) -> usize {
+        rng.gen_range(1..=self.elements)
+    }
+
+    fn parse(&self, request: &str) -> Result<usize, String> {
+        let item = number(request, "an element")?;
+        if (1..=self.elements).contains(&item) { Ok(item) } else { Err(format!("no element {}", item)) }
+    }
+
+    fn trials(&self) -> usize {
+        self.trials
+    }
+
+    // the lists fill up on first access, so both sides start from 1..=elements
+    fn online(&self, requests: &[usize], seeds: &[u64]) -> f64 {
+        mean(seeds, |seed| {
+            let mut list = (self.make)(seed);
+            for item in 1..=self.elements {
+                list.access(item);
+            }
+            requests.iter().map(|&r| list.access(r)).sum()
+        })
+    }
+
+    fn optimum(&self, requests: &[usize]) -> usize {
+        self.permutations.list_update(requests)
+    }
+}
+
+pub struct Paging {
+    make: MakeCache,
+    k: usize,
+    pages: usize,
+    trials: usize,
+}
+
+impl Paging {
+    pub fn new(algorithm: &str, params: &[String], trials: usize) -> Result<Self, String> {
+        let (make, randomized): (MakeCache, bool) = match algorithm {
+            "FIFO" => (|k, _| Box::new(FIFO::new(k)), false),
+            "FWF" => (|k, _| Box::new(FWF::new(k)), false),
+            "LRU" => (|k, _| Box::new(LRU::new(k)), false),
+            "LFU" => (|k, _| Box::new(LFU::new(k)), false),
+            "RAND" => (|k, seed| Box::new(RAND::with_seed(k, seed)), true),
+            "RMA" => (|k, seed| Box::new(RMA::with_seed(k, seed)), true),
+            _ => return Err(format!("unknown paging algorithm: {}", algorithm)),
+        };
+        let k = param(params, 0, "a cache size k")?;
+        let pages = param(params, 1, "a number of pages")?;
+        Ok(Self { make, k, pages, trials: if randomized { trials } else { 1 } })
+    }
+}
+
+impl Problem for Paging {
+    type Request = usize;
+
+    fn random(&self, rng: &mut StdRng) -> usize {
+        rng.gen_range(1..=self.pages)
+    }
+
+    fn parse(&self, request: &str) -> Result<usize, String> {
+        let page = number(request, "a page")?;
+        if (1..=self.pages).contains(&page) { Ok(page) } else { Err(format!("no page {}", page)) }
+    }
+
+    fn trials(&self) -> usize {
+        self.trials
+    }
+
+    fn online(&self, requests: &[usize], seeds: &[u64]) -> f64 {
+        mean(seeds, |seed| {
+            let mut cache = (self.make)(self.k, seed);
+            requests.iter().map(|&r| cache.access(r)).sum()
+        })
+    }
+
+    fn optimum(&self, requests: &[usize]) -> usize {
+        opt::paging(self.k, requests)
+    }
+}
+
+pub struct BinPacking {
+    make: MakePacker,
+    trials: usize,
+}
+
+impl BinPacking {
+    pub fn new(algorithm: &str, trials: usize) -> Result<Self, String> {
+        let (make, randomized): (MakePacker, bool) = match algorithm {
+            "NF" => (|_| Box::new(NextFit::new()), false),
+            "RF" => (|seed| Box::new(RandomFit::with_seed(seed)), true),
+            "FF" => (|_| Box::new(FirstFit::new()), false),
+            "BF" => (|_| Box::new(BestFit::new()), false),
+            "WF" => (|_| Box::new(WorstFit::new()), false),
+            _ => return Err(format!("unknown bin packing algorithm: {}", algorithm)),
+        };
+        Ok(Self { make, trials: if randomized { trials } else { 1 } })
+    }
+}
+
+impl Problem for BinPacking {
+    type Request = f64;
+
+    fn random(&self, rng: &mut StdRng) -> f64 {
+        rng.gen_range(1..=GRAIN) as f64 / GRAIN as f64
+    }
+
+    fn parse(&self, request: &str) -> Result<f64, String> {
+        let item: f64 = number(request, "an item size")?;
+        let units = item * GRAIN as f64;
+        if item <= 0.0 || item > 1.0 || units.fract() != 0.0 {
+            return Err(format!("item sizes are multiples of 1/{} in (0, 1], got {}", GRAIN, item));
+        }
+        Ok(item)
+    }
+
+    fn trials(&self) -> usize {
+        self.trials
+    }
+
+    fn online(&self, requests: &[f64], seeds: &[u64]) -> f64 {
+        mean(seeds, |seed| {
+            let mut packer = (self.make)(seed);
+            for &item in requests {
+                packer.pack(item).unwrap();
+            }
+            packer.no_bins()
+        })
+    }
+
+    fn optimum(&self, requests: &[f64]) -> usize {
+        let units = requests.iter().map(|item| (item * GRAIN as f64) as usize).collect::<Vec<_>>();
+        opt::bin_packing(GRAIN, &units)
+    }
+}
+
+pub struct PageMigrating {
+    make: MakeMigration,
+    graph: fn() -> Box<dyn MetricGraph>,
+    d: usize,
+    trials: usize,
+}
+
+impl PageMigrating {
+    pub fn new(algorithm: &str, params: &[String], trials: usize) -> Result<Self, String> {
+        let (make, randomized): (MakeMigration, bool) = match algorithm {
+            "MoveToMin" => (|graph, d, _| Box::new(MoveToMin::new(graph, 0, d)), false),
+            "CoinFlip" => (|graph, d, seed| Box::new(CoinFlip::with_seed(graph, 0, d, seed)), true),
+            _ => return Err(format!("unknown page migration algorithm: {}", algorithm)),
+        };
+        let graph: fn() -> Box<dyn MetricGraph> = match params.first().map(|s| s.as_str()) {
+            Some("torus3d") => || Box::new(Torus::<3>::new(4)),
+            Some("hypercube") => || Box::new(Torus::<6>::new(2)),
+            _ => return Err("page_migration needs a graph: torus3d or hypercube".to_string()),
+        };
+        let d = param(params, 1, "a migration cost d")?;
+        Ok(Self { make, graph, d, trials: if randomized { trials } else { 1 } })
+    }
+}
+
+impl Problem for PageMigrating {
+    type Request = usize;
+
+    fn random(&self, rng: &mut StdRng) -> usize {
+        rng.gen_range(0..(self.graph)().size())
+    }
+
+    fn parse(&self, request: &str) -> Result<usize, String> {
+        let node = number(request, "a node")?;
+        if node < (self.graph)().size() { Ok(node) } else { Err(format!("no node {}", node)) }
+    }
+
+    fn trials(&self) -> usize {
+        self.trials
+    }
+
+    fn online(&self, requests: &[usize], seeds: &[u64]) -> f64 {
+        mean(seeds, |seed| {
+            let mut algorithm = (self.make)((self.graph)(), self.d, seed);
+            requests.iter().map(|&r| algorithm.read(r).unwrap()).sum()
+        })
+    }
+
+    fn optimum(&self, requests: &[usize]) -> usize {
+        opt::page_migration((self.graph)().as_ref(), self.d, requests)
+    }
+}
+
+// Count reports only the cost of serving requests, so new replicas are
+// charged d here, as they are for the optimum
+struct Replications(usize);
+
+impl Observer for Replications {
+    fn event(&mut self, event: Event) {
+        if let Event::Replicate { .. } = event {
+            self.0 += 1;
+        }
+    }
+}
+
+pub struct PageAllocating {
+    nodes: usize,
+    d: usize,
+}
+
+impl PageAllocating {
+    pub fn new(algorithm: &str, params: &[String]) -> Result<Self, String> {
+        if algorithm != "Count" {
+            return Err(format!("unknown page allocation algorithm: {}", algorithm));
+        }
+        let nodes = param(params, 0, "a number of nodes")?;
+        if nodes > 12 {
+            return Err("the optimum is computed over all sets of replicas, use at most 12 nodes".to_string());
+        }
+        let d = param(params, 1, "a replication cost d")?;
+        Ok(Self { nodes, d })
+    }
+}
+
+impl Problem for PageAllocating {
+    type Request = Access;
+
+    fn random(&self, rng: &mut StdRng) -> Access {
+        let node = rng.gen_range(0..self.nodes);
+        if rng.r#gen() { Access::Write(node) } else { Access::Read(node) }
+    }
+
+    // "r3" or "3" reads at node 3, "w3" writes there
+    fn parse(&self, request: &str) -> Result<Access, String> {
+        let (write, node) = match request.strip_prefix('w') {
+            Some(node) => (true, node),
+            None => (false, request.strip_prefix('r').unwrap_or(request)),
+        };
+        let node = number(node, "r<node> or w<node>")?;
+        if node >= self.nodes {
+            return Err(format!("no node {}", node));
+        }
+        Ok(if write { Access::Write(node) } else { Access::Read(node) })
+    }
+
+    fn online(&self, requests: &[Access], _seeds: &[u64]) -> f64 {
+        let mut count = Count::new(self.nodes, 0, self.d);
+        let mut replications = Replications(0);
+        let cost = requests
+            .iter()
+            .map(|&access| match access {
+                Access::Read(node) => count.read_observed(node, &mut replications).unwrap(),
+                Access::Write(node) => count.write_observed(node, &mut replications).unwrap(),
+            })
+            .sum::<usize>();
+        (cost + self.d * replications.0) as f64
+    }
+
+    fn optimum(&self, requests: &[Access]) -> usize {
+        opt::page_allocation(self.nodes, self.d, requests)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::problems::Problem;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::fmt;
+
+// local search restarts after this many evaluations without improvement
+const STALL: usize = 500;
+const TEMPERATURE: (f64, f64) = (0.5, 0.001);
+const POPULATION: usize = 32;
+const ELITE: usize = 4;
+const TOURNAMENT: usize = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Method {
+    Local, Anneal, Genetic,
+}
+
+impl Method {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "local" => Ok(Method::Local),
+            "anneal" => Ok(Method::Anneal),
+            "genetic" => Ok(Method::Genetic),
+            _ => Err(format!("unknown method: {} (expected local, anneal or genetic)", name)),
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Local => write!(f, "local"),
+            Method::Anneal => write!(f, "anneal"),
+            Method::Genetic => write!(f, "genetic"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Found<R> {
+    pub requests: Vec<R>,
+    pub seeds: Vec<u64>,
+    pub online: f64,
+    pub optimum: usize,
+    pub ratio: f64,
+}
+
+// fresh coin seeds for one evaluation, so that the search cannot fit a
+// sequence to a fixed set of coin flips
+pub fn seeds<P: Problem>(problem: &P, rng: &mut StdRng) -> Vec<u64> {
+    (0..problem.trials()).map(|_| rng.r#gen()).collect()
+}
+
+pub fn evaluate<P: Problem>(problem: &P, requests: Vec<P::Request>, seeds: Vec<u64>) -> Found<P::Request> {
+    let online = problem.online(&requests, &seeds);
+    let optimum = problem.optimum(&requests);
+    let ratio = match optimum {
+        0 if online == 0.0 => 1.0,
+        0 => f64::INFINITY,
+        _ => online / optimum as f64,
+    };
+    Found { requests, seeds, online, optimum, ratio }
+}
+
+// the best distinct sequences seen so far, worst ratio first
+struct Archive<R> {
+    top: usize,
+    found: Vec<Found<R>>,
+}
+
+impl<R: Clone + fmt::Display> Archive<R> {
+    fn offer(&mut self, found: &Found<R>) {
+        if self.found.len() == self.top && self.found.last().is_some_and(|f| f.ratio >= found.ratio) {
+            return;
+        }
+        let key = |requests: &[R]| requests.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+        if self.found.iter().any(|f| key(&f.requests) == key(&found.requests)) {
+            return;
+        }
+        let at = self.found.partition_point(|f| f.ratio >= found.ratio);
+        self.found.insert(at, found.clone());
+        self.found.truncate(self.top);
+    }
+}
+
+fn random<P: Problem>(problem: &P, length: usize, rng: &mut StdRng) -> Vec<P::Request> {
+    (0..length).map(|_| problem.random(rng)).collect()
+}
+
+// replaces one request or exchanges two
+fn mutate<P: Problem>(problem: &P, requests: &[P::Request], rng: &mut StdRng) -> Vec<P::Request> {
+    let mut requests = requests.to_vec();
+    let i = rng.gen_range(0..requests.len());
+    if rng.r#gen() {
+        requests[i] = problem.random(rng);
+    } else {
+        let j = rng.gen_range(0..requests.len());
+        requests.swap(i, j);
+    }
+    requests
+}
+
+pub fn search<P: Problem>(problem: &P, method: Method, length: usize, iterations: usize, top: usize, rng: &mut StdRng) -> Vec<Found<P::Request>> {
+    let mut archive = Archive { top, found: Vec::new() };
+    let mut score = |requests, rng: &mut StdRng| {
+        let found = evaluate(problem, requests, seeds(problem, rng));
+        archive.offer(&found);
+        found
+    };
+
+    match method {
+        Method::Local => {
+            let mut current = score(random(problem, length, rng), rng);
+            let mut stall = 0;
+            for _ in 1..iterations {
+                let candidate = score(mutate(problem, &current.requests, rng), rng);
+                stall = if candidate.ratio > current.ratio { 0 } else { stall + 1 };
+                if candidate.ratio >= current.ratio {
+                    current = candidate;
+                }
+                if stall == STALL {
+                    current = score(random(problem, length, rng), rng);
+                    stall = 0;
+                }
+            }
+        }
+        Method::Anneal => {
+            let mut current = score(random(problem, length, rng), rng);
+            let (start, end) = TEMPERATURE;
+            for i in 1..iterations {
+                let temperature = start * (end / start).powf(i as f64 / iterations as f64);
+                let candidate = score(mutate(problem, &current.requests, rng), rng);
+                let gain = candidate.ratio - current.ratio;
+                if gain >= 0.0 || rng.r#gen::<f64>() < (gain / temperature).exp() {
+                    current = candidate;
+                }
+            }
+        }
+        Method::Genetic => {
+            let mut population = (0..POPULATION.min(iterations))
+                .map(|_| score(random(problem, length, rng), rng))
+                .collect::<Vec<_>>();
+            let mut evaluations = population.len();
+            while evaluations < iterations {
+                population.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+                let mut next = population[..ELITE.min(population.len())].to_vec();
+                while next.len() < POPULATION && evaluations < iterations {
+                    let mut pick = || {
+                        (0..TOURNAMENT)
+                            .map(|_| &population[rng.gen_range(0..population.len())])
+                            .max_by(|a, b| a.ratio.total_cmp(&b.ratio))
+                            .unwrap()
+                            .requests
+                            .clone()
+                    };
+                    let (mother, father) = (pick(), pick());
+                    let cut = rng.gen_range(0..=length);
+                    let child = mother[..cut].iter().chain(&father[cut..]).cloned().collect::<Vec<_>>();
+                    next.push(score(mutate(problem, &child, rng), rng));
+                    evaluations += 1;
+                }
+                population = next;
+            }
+        }
+    }
+
+    archive.found
+}
+
+// scores the archived sequences again on seeds the search never used, since
+// the best ratios of a noisy search are biased upwards; pairs each held-out
+// result with its ratio during the search, worst held-out ratio first
+pub fn hold_out<P: Problem>(problem: &P, found: &[Found<P::Request>], rng: &mut StdRng) -> Vec<(Found<P::Request>, f64)> {
+    let seeds = seeds(problem, rng);
+    let mut held = found
+        .iter()
+        .map(|f| (evaluate(problem, f.requests.clone(), seeds.clone()), f.ratio))
+        .collect::<Vec<_>>();
+    held.sort_by(|a, b| b.0.ratio.total_cmp(&a.0.ratio));
+    held
+}
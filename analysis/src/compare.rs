@@ -0,0 +1,215 @@
+use runner::output::{Cell, Results, Row};
+use runner::stats::welch;
+use std::collections::HashMap;
+
+pub struct Options {
+    pub alpha: f64,
+    pub min_effect: f64,
+    pub metric: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Verdict {
+    Regression, Improvement, Unchanged,
+}
+
+type Key<'a> = (&'a str, &'a Cell, &'a str);
+
+fn curves<'a>(results: &'a Results, options: &Options) -> (Vec<Key<'a>>, HashMap<Key<'a>, Vec<&'a Row>>) {
+    let mut order = Vec::new();
+    let mut curves: HashMap<Key, Vec<&Row>> = HashMap::new();
+    for row in &results.rows {
+        if options.metric.as_ref().is_some_and(|m| *m != row.metric) {
+            continue;
+        }
+        let key = (row.problem.as_str(), &row.cell, row.metric.as_str());
+        curves.entry(key).or_insert_with(|| {
+            order.push(key);
+            Vec::new()
+        }).push(row);
+    }
+    (order, curves)
+}
+
+// intermediate steps are tested at most this many per decade of the curve,
+// so that their correction stays bounded however densely it was sampled
+const STEPS_PER_DECADE: f64 = 10.0;
+
+// every metric we record is a cost, so a higher mean is always worse. The final
+// step is tested at the full alpha, the log-spaced sample of the steps before it
+// together, Bonferroni-corrected for its size
+fn compare_curve(base: &[&Row], candidate: &[&Row], options: &Options) -> (Verdict, Option<String>) {
+    let steps = candidate.iter().map(|row| (row.step, *row)).collect::<HashMap<_, _>>();
+    let mut tests = base
+        .iter()
+        .filter_map(|a| {
+            let b = steps.get(&a.step)?;
+            Some((a.step, a.summary, b.summary, welch(&a.summary, &b.summary)?))
+        })
+        .collect::<Vec<_>>();
+    tests.sort_by_key(|t| t.0);
+    let Some((&(step, a, b, test), before)) = tests.split_last() else {
+        return (Verdict::Unchanged, None);
+    };
+
+    // the last tested step of each log-spaced bucket
+    let bucket = |step: usize| (((step + 1) as f64).log10() * STEPS_PER_DECADE).floor() as usize;
+    let intermediate = before
+        .iter()
+        .enumerate()
+        .filter(|&(i, t)| before.get(i + 1).is_none_or(|next| bucket(next.0) != bucket(t.0)))
+        .map(|(_, t)| *t)
+        .collect::<Vec<_>>();
+    let corrected = options.alpha / intermediate.len().max(1) as f64;
+
+    let changed = |a: f64, b: f64, p: f64, alpha: f64| p < alpha && (b - a).abs() > options.min_effect * a.abs();
+    let first = |worse: bool| {
+        intermediate
+            .iter()
+            .map(|t| (t, corrected))
+            .chain([(&tests[tests.len() - 1], options.alpha)])
+            .find(|((_, a, b, test), alpha)| changed(a.mean, b.mean, test.p, *alpha) && (b.mean > a.mean) == worse)
+            .map(|(t, _)| t.0)
+    };
+
+    let verdict = match (first(true), first(false)) {
+        (Some(_), _) => Verdict::Regression,
+        (None, Some(_)) => Verdict::Improvement,
+        (None, None) => Verdict::Unchanged,
+    };
+    let mut report = format!(
+        "final step {}: {:.4} -> {:.4} ({:+.2}%, p = {:.2e})",
+        step, a.mean, b.mean, 100.0 * (b.mean - a.mean) / a.mean.abs(), test.p
+    );
+    if let Some(from) = first(verdict != Verdict::Improvement) {
+        report += &format!(", significant from step {}", from);
+    }
+    (verdict, Some(report))
+}
+
+// prints every changed curve and a summary; returns whether the candidate
+// is free of regressions and covers every cell of the baseline
+pub fn compare(base: &Results, candidate: &Results, options: &Options) -> bool {
+    let (order, base_curves) = curves(base, options);
+    let (candidate_order, candidate_curves) = curves(candidate, options);
+
+    let mut counts = HashMap::new();
+    for key in &order {
+        let (problem, cell, metric) = key;
+        let Some(other) = candidate_curves.get(key) else {
+            println!("missing      {}: {} [{}] is only in the baseline", problem, cell, metric);
+            continue;
+        };
+
+        let (verdict, report) = compare_curve(&base_curves[key], other, options);
+        *counts.entry(verdict).or_insert(0) += 1;
+        let label = match verdict {
+            Verdict::Regression => "regression",
+            Verdict::Improvement => "improvement",
+            Verdict::Unchanged => continue,
+        };
+        println!("{:<12} {}: {} [{}] {}", label, problem, cell, metric, report.unwrap_or_default());
+    }
+
+    let added = candidate_order.iter().filter(|key| !base_curves.contains_key(*key)).collect::<Vec<_>>();
+    for (problem, cell, metric) in &added {
+        println!("added        {}: {} [{}] is only in the candidate", problem, cell, metric);
+    }
+
+    let count = |verdict| counts.get(&verdict).copied().unwrap_or(0);
+    let compared = count(Verdict::Regression) + count(Verdict::Improvement) + count(Verdict::Unchanged);
+    let missing = order.len() - compared;
+    println!(
+        "compared {} curves: {} regressions, {} improvements, {} unchanged; {} missing, {} added",
+        compared, count(Verdict::Regression), count(Verdict::Improvement), count(Verdict::Unchanged), missing, added.len()
+    );
+
+    count(Verdict::Regression) == 0 && missing == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runner::stats::Summary;
+
+    // the two samples of the Welch example in runner's stats tests, where
+    // scipy gives p = 0.02138 for the increase from the first to the second
+    fn summary(mean: f64, variance: f64) -> Summary {
+        Summary { reps: 15, mean, variance, std_err: 0.0, ci_low: 0.0, ci_high: 0.0, median: mean, p95: mean }
+    }
+
+    fn curve(steps: usize, summary: Summary) -> Vec<Row> {
+        (1..=steps)
+            .map(|step| Row { problem: "p".to_string(), cell: Cell::new("A", "W", vec![]), metric: "cost".to_string(), step, summary })
+            .collect()
+    }
+
+    fn verdict(base: &[Row], candidate: &[Row], options: &Options) -> Verdict {
+        let (base, candidate) = (base.iter().collect::<Vec<_>>(), candidate.iter().collect::<Vec<_>>());
+        compare_curve(&base, &candidate, options).0
+    }
+
+    const OPTIONS: Options = Options { alpha: 0.05, min_effect: 0.0, metric: None };
+    const LOW: (f64, f64) = (20.82, 7.867428571428571);
+    const HIGH: (f64, f64) = (22.986666666666667, 3.812666666666667);
+
+    #[test]
+    fn a_higher_cost_is_a_regression() {
+        let (low, high) = (summary(LOW.0, LOW.1), summary(HIGH.0, HIGH.1));
+        assert!(verdict(&curve(1, low), &curve(1, high), &OPTIONS) == Verdict::Regression);
+        assert!(verdict(&curve(1, high), &curve(1, low), &OPTIONS) == Verdict::Improvement);
+        assert!(verdict(&curve(1, low), &curve(1, low), &OPTIONS) == Verdict::Unchanged);
+    }
+
+    #[test]
+    fn intermediate_steps_are_bonferroni_corrected() {
+        let (low, high) = (summary(LOW.0, LOW.1), summary(HIGH.0, HIGH.1));
+        // the final step is unchanged and p = 0.0214 at the steps before it,
+        // below 0.05 / 2 but not below 0.05 / 3
+        let raised = |steps: usize| {
+            let mut curve = curve(steps + 1, high);
+            curve.last_mut().unwrap().summary = low;
+            curve
+        };
+        assert!(verdict(&curve(3, low), &raised(2), &OPTIONS) == Verdict::Regression);
+        assert!(verdict(&curve(4, low), &raised(3), &OPTIONS) == Verdict::Unchanged);
+        let strict = Options { alpha: 0.02, ..OPTIONS };
+        assert!(verdict(&curve(1, low), &curve(1, high), &strict) == Verdict::Unchanged);
+    }
+
+    #[test]
+    fn the_final_step_is_tested_at_the_full_alpha() {
+        let (low, high) = (summary(LOW.0, LOW.1), summary(HIGH.0, HIGH.1));
+        // a densely sampled curve regressing only at its final step
+        let mut raised = curve(10000, low);
+        raised.last_mut().unwrap().summary = high;
+        assert!(verdict(&curve(10000, low), &raised, &OPTIONS) == Verdict::Regression);
+        // p = 3e-4 at every step before it survives the correction over the
+        // 40 log-spaced steps, though not one over all 9999
+        let mut raised = curve(10000, summary(24.5, HIGH.1));
+        raised.last_mut().unwrap().summary = low;
+        assert!(verdict(&curve(10000, low), &raised, &OPTIONS) == Verdict::Regression);
+    }
+
+    #[test]
+    fn small_effects_are_ignored() {
+        let (low, high) = (summary(LOW.0, LOW.1), summary(HIGH.0, HIGH.1));
+        // the means differ by 10.4%
+        let options = Options { min_effect: 0.1, ..OPTIONS };
+        assert!(verdict(&curve(1, low), &curve(1, high), &options) == Verdict::Regression);
+        let options = Options { min_effect: 0.11, ..OPTIONS };
+        assert!(verdict(&curve(1, low), &curve(1, high), &options) == Verdict::Unchanged);
+    }
+
+    #[test]
+    fn the_gate_fails_on_regressions_and_missing_curves() {
+        let results = |summary: Summary| Results { param_names: vec![], rows: curve(1, summary) };
+        let (low, high) = (results(summary(LOW.0, LOW.1)), results(summary(HIGH.0, HIGH.1)));
+        assert!(compare(&low, &low, &OPTIONS));
+        assert!(!compare(&low, &high, &OPTIONS));
+        assert!(compare(&high, &low, &OPTIONS));
+        let empty = Results { param_names: vec![], rows: vec![] };
+        assert!(!compare(&low, &empty, &OPTIONS));
+        assert!(compare(&empty, &low, &OPTIONS));
+    }
+}
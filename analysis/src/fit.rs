@@ -0,0 +1,253 @@
+use runner::output::{Cell, Results, Row};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+type Coefficients = [f64; 2];
+
+// models are linear in their coefficients after transforming y, so each is a
+// basis evaluated at n plus the inverse of that transform
+struct Model {
+    name: &'static str,
+    coefficients: [&'static str; 2],
+    basis: fn(f64) -> [f64; 2],
+    log_y: bool,
+    // turns fitted coefficients and their standard errors into model units
+    report: fn(&mut Coefficients, &mut Coefficients),
+}
+
+fn identity(_: &mut Coefficients, _: &mut Coefficients) {}
+
+const COST_MODELS: [Model; 3] = [
+    Model { name: "a*n", coefficients: ["a", ""], basis: |n| [n, 0.0], log_y: false, report: identity },
+    Model { name: "a*n + b*log(n)", coefficients: ["a", "b"], basis: |n| [n, n.ln()], log_y: false, report: identity },
+    // fitted as log y = log a + b log n; the error of a follows by the delta method
+    Model {
+        name: "a*n^b",
+        coefficients: ["a", "b"],
+        basis: |n| [1.0, n.ln()],
+        log_y: true,
+        report: |c, se| {
+            c[0] = c[0].exp();
+            se[0] *= c[0];
+        },
+    },
+];
+
+const LEVEL_MODELS: [Model; 2] = [
+    Model { name: "c", coefficients: ["c", ""], basis: |_| [1.0, 0.0], log_y: false, report: identity },
+    Model { name: "c + b/n", coefficients: ["c", "b"], basis: |n| [1.0, 1.0 / n], log_y: false, report: identity },
+];
+
+// prefix costs grow with n; every other metric is expected to level off
+fn models(metric: &str) -> &'static [Model] {
+    if metric == "cost" { &COST_MODELS } else { &LEVEL_MODELS }
+}
+
+pub struct Fit {
+    pub estimates: Vec<(&'static str, f64, f64)>,
+    pub rmse: f64,
+    pub points: usize,
+}
+
+// ordinary least squares on one or two basis functions (unused ones are all
+// zero), with standard errors from the residual variance
+fn least_squares(points: &[(f64, f64)], model: &Model) -> Option<Fit> {
+    let k = if model.coefficients[1].is_empty() { 1 } else { 2 };
+    let rows = points
+        .iter()
+        .filter(|&&(_, y)| !model.log_y || y > 0.0)
+        .map(|&(n, y)| ((model.basis)(n), if model.log_y { y.ln() } else { y }))
+        .collect::<Vec<_>>();
+    if rows.len() <= k {
+        return None;
+    }
+
+    let mut xtx = [[0.0; 2]; 2];
+    let mut xty = [0.0; 2];
+    for (x, y) in &rows {
+        for i in 0..k {
+            xty[i] += x[i] * y;
+            for j in 0..k {
+                xtx[i][j] += x[i] * x[j];
+            }
+        }
+    }
+
+    let inverse = if k == 1 {
+        [[1.0 / xtx[0][0], 0.0], [0.0, 0.0]]
+    } else {
+        let det = xtx[0][0] * xtx[1][1] - xtx[0][1] * xtx[1][0];
+        [[xtx[1][1] / det, -xtx[0][1] / det], [-xtx[1][0] / det, xtx[0][0] / det]]
+    };
+    if !inverse.iter().flatten().all(|v| v.is_finite()) {
+        return None;
+    }
+
+    let mut coefficients = [0.0; 2];
+    for i in 0..k {
+        coefficients[i] = (0..k).map(|j| inverse[i][j] * xty[j]).sum();
+    }
+
+    let residuals = rows
+        .iter()
+        .map(|(x, y)| y - (0..k).map(|i| coefficients[i] * x[i]).sum::<f64>())
+        .map(|r| r * r)
+        .sum::<f64>();
+    let variance = residuals / (rows.len() - k) as f64;
+    let mut errors = [0.0; 2];
+    for i in 0..k {
+        errors[i] = (variance * inverse[i][i]).sqrt();
+    }
+
+    (model.report)(&mut coefficients, &mut errors);
+
+    // report the error of the fit in the units of y, also for the log model
+    let rmse = if model.log_y {
+        let c = coefficients;
+        (points.iter().map(|&(n, y)| (y - c[0] * n.powf(c[1])).powi(2)).sum::<f64>() / points.len() as f64).sqrt()
+    } else {
+        (residuals / rows.len() as f64).sqrt()
+    };
+
+    Some(Fit {
+        estimates: (0..k).map(|i| (model.coefficients[i], coefficients[i], errors[i])).collect(),
+        rmse,
+        points: rows.len(),
+    })
+}
+
+// the model with the lowest BIC, n log(rmse²) + k log n, so that a second
+// coefficient has to earn its place; the rmse is floored relative to the
+// largest value, so exact fits tie and the simpler one wins
+fn select(points: &[(f64, f64)], fits: &[(&Model, Fit)]) -> Option<usize> {
+    let floor = 1e-9 * points.iter().map(|(_, y)| y.abs()).fold(f64::MIN_POSITIVE, f64::max);
+    let bic = |fit: &Fit| {
+        let n = fit.points as f64;
+        n * fit.rmse.max(floor).powi(2).ln() + fit.estimates.len() as f64 * n.ln()
+    };
+    (0..fits.len()).min_by(|&i, &j| bic(&fits[i].1).total_cmp(&bic(&fits[j].1)))
+}
+
+// fits every model to the tail of every curve, starting at `from` times its last step
+pub fn fit(results: &Results, from: f64, metric: Option<&str>, out: &Path) -> io::Result<()> {
+    let mut order = Vec::new();
+    let mut curves: HashMap<(&str, &Cell, &str), Vec<&Row>> = HashMap::new();
+    for row in &results.rows {
+        if metric.is_some_and(|m| m != row.metric) {
+            continue;
+        }
+        let key = (row.problem.as_str(), &row.cell, row.metric.as_str());
+        curves.entry(key).or_insert_with(|| {
+            order.push(key);
+            Vec::new()
+        }).push(row);
+    }
+
+    let mut csv = format!(
+        "problem,algorithm,workload,{}metric,model,coefficient,estimate,std_err,rmse,points\n",
+        results.param_names.iter().map(|p| format!("{},", p)).collect::<String>()
+    );
+    for key in order {
+        let (problem, cell, metric) = key;
+        let rows = &curves[&key];
+        let last = rows.iter().map(|row| row.step).max().unwrap_or(0);
+        let points = rows
+            .iter()
+            .filter(|row| row.step as f64 >= from * last as f64)
+            .map(|row| (row.step as f64, row.summary.mean))
+            .collect::<Vec<_>>();
+
+        println!("{}: {} [{}], steps {}..={}", problem, cell, metric, (from * last as f64).ceil().max(1.0), last);
+        let mut fits = Vec::new();
+        for model in models(metric) {
+            let Some(fit) = least_squares(&points, model) else {
+                println!("  {:<16} too few points", model.name);
+                continue;
+            };
+
+            let estimates = fit.estimates
+                .iter()
+                .map(|(name, estimate, error)| format!("{} = {:.6} ± {:.6}", name, estimate, error))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("  {:<16} {} (rmse {:.4})", model.name, estimates, fit.rmse);
+
+            for (name, estimate, error) in &fit.estimates {
+                let fields = [problem, &cell.algorithm, &cell.workload]
+                    .into_iter()
+                    .chain(cell.params.iter().map(|p| p.as_str()))
+                    .chain([metric, model.name, name])
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(csv, "{},{},{},{},{}", fields, estimate, error, fit.rmse, fit.points).unwrap();
+            }
+            fits.push((model, fit));
+        }
+        if let Some(best) = select(&points, &fits) {
+            println!("  best: {}", fits[best].0.name);
+        }
+    }
+
+    fs::write(out, csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(f: impl Fn(f64) -> f64) -> Vec<(f64, f64)> {
+        (1..=50).map(|i| i as f64 * 20.0).map(|n| (n, f(n))).collect()
+    }
+
+    fn close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-6 * expected.abs().max(1.0), "{} vs {}", actual, expected);
+    }
+
+    // fits every model, checks the coefficients of the named one and that it is selected
+    fn check(models: &[Model], points: &[(f64, f64)], name: &str, expected: &[f64]) {
+        let fits = models.iter().filter_map(|model| Some((model, least_squares(points, model)?))).collect::<Vec<_>>();
+        let (_, fit) = fits.iter().find(|(model, _)| model.name == name).unwrap();
+        assert_eq!(fit.estimates.len(), expected.len());
+        for (&(_, estimate, error), &expected) in fit.estimates.iter().zip(expected) {
+            close(estimate, expected);
+            assert!(error < 1e-6, "{} has standard error {}", name, error);
+        }
+        assert!(fit.rmse < 1e-6 * points.last().unwrap().1.abs());
+        assert_eq!(fits[select(points, &fits).unwrap()].0.name, name);
+    }
+
+    #[test]
+    fn recovers_exact_cost_curves() {
+        check(&COST_MODELS, &curve(|n| 3.0 * n), "a*n", &[3.0]);
+        check(&COST_MODELS, &curve(|n| 2.0 * n + 5.0 * n.ln()), "a*n + b*log(n)", &[2.0, 5.0]);
+        check(&COST_MODELS, &curve(|n| 1.5 * n - 40.0 * n.ln()), "a*n + b*log(n)", &[1.5, -40.0]);
+        check(&COST_MODELS, &curve(|n| 0.5 * n.powf(1.5)), "a*n^b", &[0.5, 1.5]);
+    }
+
+    #[test]
+    fn recovers_exact_level_curves() {
+        check(&LEVEL_MODELS, &curve(|_| 1.7), "c", &[1.7]);
+        check(&LEVEL_MODELS, &curve(|n| 1.25 + 3.0 / n), "c + b/n", &[1.25, 3.0]);
+    }
+
+    #[test]
+    fn noise_shows_in_the_errors() {
+        // alternating noise of ±1 around 4n
+        let points = curve(|n| 4.0 * n + if ((n / 20.0) as usize).is_multiple_of(2) { 1.0 } else { -1.0 });
+        let fit = least_squares(&points, &COST_MODELS[0]).unwrap();
+        let (_, a, error) = fit.estimates[0];
+        assert!((a - 4.0).abs() < 3.0 * error && error > 0.0, "a = {} ± {}", a, error);
+        assert!(fit.rmse > 0.9 && fit.rmse <= 1.0, "rmse {}", fit.rmse);
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert!(least_squares(&[(1.0, 1.0), (2.0, 2.0)], &COST_MODELS[1]).is_none());
+        assert!(least_squares(&[(1.0, 1.0), (2.0, 2.0)], &COST_MODELS[0]).is_some());
+        // the power law needs positive values
+        assert!(least_squares(&[(1.0, 0.0), (2.0, 0.0), (3.0, 1.0)], &COST_MODELS[2]).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use runner::manifest::{Manifest, MANIFEST};
+use runner::merge::merge;
+use runner::output::{read_results, read_results_jsonl, Results};
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod compare;
+mod fit;
+mod plot;
+mod svg;
+
+const USAGE: &str = "usage:
+  analysis plot <results dir> [--out <dir>] [--no-bands]
+  analysis compare <baseline dir> <candidate dir> [--alpha <p>] [--min-effect <rel>] [--metric <name>]
+  analysis fit <results dir> [--from <fraction of last step>] [--metric <name>] [--out <file>]
+  analysis merge <shard dir>... --out <dir>";
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+// reads the results of a run and checks them against its manifest, if there is one;
+// a run written with --format jsonl has only results.jsonl
+fn load(dir: &Path) -> io::Result<(Results, Option<Manifest>)> {
+    let csv = dir.join("results.csv");
+    let results = if csv.exists() { read_results(&csv)? } else { read_results_jsonl(&dir.join("results.jsonl"))? };
+
+    if !dir.join(MANIFEST).exists() {
+        eprintln!("warning: {} has no {}", dir.display(), MANIFEST);
+        return Ok((results, None));
+    }
+    let manifest = Manifest::load(dir)?;
+    manifest.validate(&results).map_err(|e| invalid(format!("{}: {}", dir.display(), e)))?;
+    if manifest.finished.is_none() {
+        eprintln!("warning: the run in {} has not finished", dir.display());
+    }
+
+    Ok((results, Some(manifest)))
+}
+
+fn plot_command(args: &[String]) -> io::Result<()> {
+    let mut dir = None;
+    let mut out = None;
+    let mut bands = true;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(args.next().ok_or_else(|| invalid(USAGE.to_string()))?)),
+            "--no-bands" => bands = false,
+            _ if dir.is_none() => dir = Some(PathBuf::from(arg)),
+            _ => return Err(invalid(format!("unexpected argument: {}\n{}", arg, USAGE))),
+        }
+    }
+
+    let dir = dir.ok_or_else(|| invalid(USAGE.to_string()))?;
+    let out = out.unwrap_or_else(|| dir.join("figures"));
+    let (results, _) = load(&dir)?;
+
+    for path in plot::plot(&results, &out, bands)? {
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+fn compare_command(args: &[String]) -> io::Result<bool> {
+    let mut dirs = Vec::new();
+    let mut options = compare::Options { alpha: 0.05, min_effect: 0.0, metric: None };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| invalid(USAGE.to_string()));
+        let number = |v: &String| v.parse::<f64>().map_err(|_| invalid(format!("{} expects a number, got {}", arg, v)));
+        match arg.as_str() {
+            "--alpha" => options.alpha = number(value()?)?,
+            "--min-effect" => options.min_effect = number(value()?)?,
+            "--metric" => options.metric = Some(value()?.clone()),
+            _ if dirs.len() < 2 => dirs.push(PathBuf::from(arg)),
+            _ => return Err(invalid(format!("unexpected argument: {}\n{}", arg, USAGE))),
+        }
+    }
+    let [base_dir, candidate_dir] = &dirs[..] else {
+        return Err(invalid(USAGE.to_string()));
+    };
+
+    let (base, base_manifest) = load(base_dir)?;
+    let (candidate, candidate_manifest) = load(candidate_dir)?;
+    if base.param_names != candidate.param_names {
+        return Err(invalid(format!("cannot align cells: parameters {:?} and {:?} differ", base.param_names, candidate.param_names)));
+    }
+    if let (Some(a), Some(b)) = (&base_manifest, &candidate_manifest) {
+        if a.settings != b.settings {
+            eprintln!("warning: runs have different settings: {} and {}", a.to_json()["settings"], b.to_json()["settings"]);
+        }
+        if a.seed == b.seed && a.git_revision == b.git_revision && !a.git_dirty && !b.git_dirty {
+            eprintln!("warning: both runs come from the same clean revision with the same seed");
+        }
+    }
+
+    Ok(compare::compare(&base, &candidate, &options))
+}
+
+fn fit_command(args: &[String]) -> io::Result<()> {
+    let mut dir = None;
+    let mut out = None;
+    let mut from = 0.1;
+    let mut metric = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| invalid(USAGE.to_string()));
+        match arg.as_str() {
+            "--from" => {
+                let v = value()?;
+                from = v.parse().ok().filter(|f| (0.0..=1.0).contains(f)).ok_or_else(|| invalid(format!("--from expects a fraction, got {}", v)))?;
+            }
+            "--metric" => metric = Some(value()?.clone()),
+            "--out" => out = Some(PathBuf::from(value()?)),
+            _ if dir.is_none() => dir = Some(PathBuf::from(arg)),
+            _ => return Err(invalid(format!("unexpected argument: {}\n{}", arg, USAGE))),
+        }
+    }
+
+    let dir = dir.ok_or_else(|| invalid(USAGE.to_string()))?;
+    let out = out.unwrap_or_else(|| dir.join("fits.csv"));
+    let (results, _) = load(&dir)?;
+
+    fit::fit(&results, from, metric.as_deref(), &out)
+}
+
+fn merge_command(args: &[String]) -> io::Result<()> {
+    let mut shards = Vec::new();
+    let mut out = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(args.next().ok_or_else(|| invalid(USAGE.to_string()))?)),
+            _ => shards.push(PathBuf::from(arg)),
+        }
+    }
+    let out = out.ok_or_else(|| invalid(USAGE.to_string()))?;
+
+    let manifest = merge(&shards, &out)?;
+    println!("merged {} shards with {} cells into {}", shards.len(), manifest.cells.len(), out.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("plot") => plot_command(&args[1..]).map(|_| true),
+        Some("compare") => compare_command(&args[1..]),
+        Some("fit") => fit_command(&args[1..]).map(|_| true),
+        Some("merge") => merge_command(&args[1..]).map(|_| true),
+        _ => Err(invalid(USAGE.to_string())),
+    };
+
+    // 1 signals a regression found by compare, 2 a failure to run at all
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
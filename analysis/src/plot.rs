@@ -0,0 +1,228 @@
+use crate::svg::{Figure, Panel, Point, Series};
+use runner::output::{Cell, Results, Row};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Copy, Clone, PartialEq)]
+enum X {
+    Step, Param(&'static str),
+}
+
+// how one family of figures is cut out of a results file: one figure per
+// distinct `figure_by` value, one panel per workload, one line per `series_by`
+struct Layout {
+    name: String,
+    metric: String,
+    figure_by: Vec<String>,
+    series_by: Vec<String>,
+    x: X,
+    log_x: bool,
+    per_step: bool,
+    x_label: String,
+    y_label: String,
+}
+
+impl Layout {
+    fn new(name: &str, metric: &str, figure_by: &[&str], series_by: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            metric: metric.to_string(),
+            figure_by: figure_by.iter().map(|s| s.to_string()).collect(),
+            series_by: series_by.iter().map(|s| s.to_string()).collect(),
+            x: X::Step,
+            log_x: true,
+            per_step: true,
+            x_label: "no. requests".to_string(),
+            y_label: "avg cost".to_string(),
+        }
+    }
+}
+
+fn layouts(problem: &str, results: &Results) -> Vec<Layout> {
+    match problem {
+        "list_update" => vec![Layout {
+            x_label: "n".to_string(),
+            y_label: "operations".to_string(),
+            ..Layout::new("cost", "cost", &[], &["algorithm"])
+        }],
+        "paging" => vec![
+            Layout::new("cost", "cost", &["n", "k"], &["algorithm"]),
+            Layout {
+                x: X::Param("k"),
+                log_x: false,
+                x_label: "k".to_string(),
+                ..Layout::new("cost_by_k", "cost", &["n"], &["algorithm"])
+            },
+        ],
+        "bin_packing" => vec![Layout {
+            per_step: false,
+            x_label: "no. items".to_string(),
+            y_label: "competitive ratio".to_string(),
+            ..Layout::new("ratio", "ratio", &[], &["algorithm"])
+        }],
+        "page_migration" => vec![Layout::new("cost", "cost", &["d"], &["algorithm", "graph"])],
+        "page_allocation" => vec![
+            Layout::new("cost", "cost", &["d"], &["p"]),
+            Layout {
+                per_step: false,
+                y_label: "no. pages".to_string(),
+                ..Layout::new("pages", "pages", &["d"], &["p"])
+            },
+        ],
+        _ => {
+            let params = results.param_names.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+            let mut metrics: Vec<&str> = Vec::new();
+            for row in &results.rows {
+                if !metrics.contains(&row.metric.as_str()) {
+                    metrics.push(&row.metric);
+                }
+            }
+            metrics
+                .into_iter()
+                .map(|metric| Layout {
+                    per_step: metric == "cost",
+                    y_label: metric.to_string(),
+                    ..Layout::new(metric, metric, &params, &["algorithm"])
+                })
+                .collect()
+        }
+    }
+}
+
+// groups rows by key, keeping the order in which keys first appear
+fn group_by<'a, K, F>(rows: &[&'a Row], key: F) -> Vec<(K, Vec<&'a Row>)>
+where K: Clone + Eq + Hash, F: Fn(&Row) -> K
+{
+    let mut index: HashMap<K, usize> = HashMap::new();
+    let mut groups: Vec<(K, Vec<&Row>)> = Vec::new();
+    for &row in rows {
+        let k = key(row);
+        match index.get(&k) {
+            Some(&i) => groups[i].1.push(row),
+            None => {
+                index.insert(k.clone(), groups.len());
+                groups.push((k, vec![row]));
+            }
+        }
+    }
+    groups
+}
+
+fn describe(columns: &[String], values: &[String], separator: &str) -> String {
+    columns
+        .iter()
+        .zip(values)
+        .map(|(c, v)| match c.as_str() {
+            "algorithm" | "workload" | "graph" => v.clone(),
+            _ => format!("{} = {}", c, v),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn point(row: &Row, x: f64, per_step: bool) -> Point {
+    let scale = if per_step { row.step as f64 } else { 1.0 };
+    Point {
+        x,
+        y: row.summary.mean / scale,
+        low: row.summary.ci_low / scale,
+        high: row.summary.ci_high / scale,
+    }
+}
+
+fn figures(results: &Results, problem: &str, layout: &Layout, bands: bool) -> Vec<(String, Figure)> {
+    let values = |row: &Row, columns: &[String]| {
+        columns
+            .iter()
+            .map(|c| results.value(row, c).unwrap_or_default().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let mut rows = results.rows
+        .iter()
+        .filter(|row| row.problem == problem && row.metric == layout.metric)
+        .collect::<Vec<_>>();
+    if let X::Param(_) = layout.x {
+        let mut last: HashMap<&Cell, usize> = HashMap::new();
+        for row in &rows {
+            let step = last.entry(&row.cell).or_default();
+            *step = usize::max(*step, row.step);
+        }
+        rows.retain(|row| last[&row.cell] == row.step);
+    }
+
+    group_by(&rows, |row| values(row, &layout.figure_by))
+        .into_iter()
+        .map(|(figure_key, rows)| {
+            let panels = group_by(&rows, |row| row.cell.workload.clone())
+                .into_iter()
+                .map(|(workload, rows)| {
+                    let series = group_by(&rows, |row| values(row, &layout.series_by))
+                        .into_iter()
+                        .map(|(series_key, rows)| {
+                            let mut points = rows
+                                .iter()
+                                .map(|row| {
+                                    let x = match layout.x {
+                                        X::Step => row.step as f64,
+                                        X::Param(p) => results.value(row, p).and_then(|v| v.parse().ok()).unwrap_or(f64::NAN),
+                                    };
+                                    point(row, x, layout.per_step)
+                                })
+                                .collect::<Vec<_>>();
+                            points.sort_by(|a, b| a.x.total_cmp(&b.x));
+                            Series { label: describe(&layout.series_by, &series_key, " + "), points }
+                        })
+                        .collect();
+                    Panel { title: workload, series }
+                })
+                .collect();
+
+            let mut name = format!("{}_{}", problem, layout.name);
+            for value in &figure_key {
+                name += &format!("_{}", value);
+            }
+            let mut title = problem.replace('_', " ");
+            if !figure_key.is_empty() {
+                title += &format!(": {}", describe(&layout.figure_by, &figure_key, ", "));
+            }
+
+            let figure = Figure {
+                title,
+                x_label: layout.x_label.clone(),
+                y_label: layout.y_label.clone(),
+                log_x: layout.log_x,
+                bands,
+                panels,
+            };
+            (name, figure)
+        })
+        .collect()
+}
+
+pub fn plot(results: &Results, out: &Path, bands: bool) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(out)?;
+
+    let mut problems: Vec<&str> = Vec::new();
+    for row in &results.rows {
+        if !problems.contains(&row.problem.as_str()) {
+            problems.push(&row.problem);
+        }
+    }
+
+    let mut written = Vec::new();
+    for problem in problems {
+        for layout in layouts(problem, results) {
+            for (name, figure) in figures(results, problem, &layout, bands) {
+                let path = out.join(format!("{}.svg", name));
+                fs::write(&path, figure.render())?;
+                written.push(path);
+            }
+        }
+    }
+
+    Ok(written)
+}
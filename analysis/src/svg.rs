@@ -0,0 +1,235 @@
+use std::fmt::Write;
+
+const PANEL_WIDTH: f64 = 480.0;
+const PANEL_HEIGHT: f64 = 360.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const TITLE_HEIGHT: f64 = 40.0;
+const COLUMNS: usize = 2;
+
+const COLORS: [&str; 10] = [
+    "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00",
+    "#17becf", "#a65628", "#f781bf", "#999999", "#bcbd22",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+pub struct Series {
+    pub label: String,
+    pub points: Vec<Point>,
+}
+
+pub struct Panel {
+    pub title: String,
+    pub series: Vec<Series>,
+}
+
+pub struct Figure {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub log_x: bool,
+    pub bands: bool,
+    pub panels: Vec<Panel>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn format_tick(v: f64) -> String {
+    if v != 0.0 && (v.abs() >= 1e5 || v.abs() < 1e-3) {
+        return format!("{:e}", v);
+    }
+    let s = format!("{:.3}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn linear_ticks(lo: f64, hi: f64) -> Vec<f64> {
+    let raw = (hi - lo) / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&s| (hi - lo) / s <= 6.0)
+        .unwrap_or(10.0 * magnitude);
+
+    let mut ticks = Vec::new();
+    let mut t = (lo / step).ceil() * step;
+    while t <= hi + step * 1e-9 {
+        ticks.push(if t.abs() < step * 1e-9 { 0.0 } else { t });
+        t += step;
+    }
+    ticks
+}
+
+fn log_ticks(lo: f64, hi: f64) -> Vec<f64> {
+    let first = lo.log10().ceil() as i32;
+    let last = hi.log10().floor() as i32;
+    (first..=last).map(|e| 10f64.powi(e)).collect()
+}
+
+struct Axes {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    log_x: bool,
+}
+
+impl Axes {
+    fn tx(&self, x: f64) -> f64 {
+        if self.log_x { x.ln() } else { x }
+    }
+
+    fn sx(&self, x: f64) -> f64 {
+        let (lo, hi) = (self.tx(self.x_range.0), self.tx(self.x_range.1));
+        self.left + (self.tx(x) - lo) / (hi - lo) * self.width
+    }
+
+    fn sy(&self, y: f64) -> f64 {
+        let (lo, hi) = self.y_range;
+        self.top + self.height - (y - lo) / (hi - lo) * self.height
+    }
+}
+
+fn range<I: Iterator<Item = f64>>(values: I) -> Option<(f64, f64)> {
+    values
+        .filter(|v| v.is_finite())
+        .fold(None, |acc, v| match acc {
+            None => Some((v, v)),
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+        })
+}
+
+// drops points that would land on the same pixel column as the previous one
+fn thin(axes: &Axes, points: &[Point]) -> Vec<Point> {
+    let mut kept: Vec<Point> = Vec::new();
+    let mut last_px = f64::NEG_INFINITY;
+    for (i, p) in points.iter().enumerate() {
+        let px = axes.sx(p.x);
+        if px - last_px >= 0.5 || i + 1 == points.len() {
+            kept.push(*p);
+            last_px = px;
+        }
+    }
+    kept
+}
+
+impl Figure {
+    fn labels(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = Vec::new();
+        for series in self.panels.iter().flat_map(|p| &p.series) {
+            if !labels.contains(&series.label.as_str()) {
+                labels.push(&series.label);
+            }
+        }
+        labels
+    }
+
+    pub fn render(&self) -> String {
+        let columns = self.panels.len().clamp(1, COLUMNS);
+        let rows = self.panels.len().div_ceil(columns).max(1);
+        let width = columns as f64 * PANEL_WIDTH;
+        let height = TITLE_HEIGHT + rows as f64 * PANEL_HEIGHT;
+        let labels = self.labels();
+
+        let mut svg = String::new();
+        writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#, w = width, h = height).unwrap();
+        writeln!(svg, r#"<rect width="{}" height="{}" fill="white"/>"#, width, height).unwrap();
+        writeln!(svg, r#"<text x="{}" y="26" font-size="18" text-anchor="middle">{}</text>"#, width / 2.0, escape(&self.title)).unwrap();
+
+        for (i, panel) in self.panels.iter().enumerate() {
+            let x0 = (i % columns) as f64 * PANEL_WIDTH;
+            let y0 = TITLE_HEIGHT + (i / columns) as f64 * PANEL_HEIGHT;
+            self.render_panel(&mut svg, panel, x0, y0, &labels);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn render_panel(&self, svg: &mut String, panel: &Panel, x0: f64, y0: f64, labels: &[&str]) {
+        let points = || panel.series.iter().flat_map(|s| &s.points);
+        let x_range = range(points().map(|p| p.x).filter(|&x| !self.log_x || x > 0.0)).unwrap_or((1.0, 10.0));
+        let y_values = points().flat_map(|p| if self.bands { vec![p.y, p.low, p.high] } else { vec![p.y] });
+        let (y_lo, y_hi) = range(y_values).unwrap_or((0.0, 1.0));
+        let pad = if y_hi > y_lo { 0.05 * (y_hi - y_lo) } else { 0.5 };
+
+        let axes = Axes {
+            left: x0 + MARGIN_LEFT,
+            top: y0 + MARGIN_TOP,
+            width: PANEL_WIDTH - MARGIN_LEFT - MARGIN_RIGHT,
+            height: PANEL_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM,
+            x_range: if x_range.0 < x_range.1 { x_range } else { (x_range.0 * 0.5, x_range.0 * 2.0 + 1.0) },
+            y_range: (y_lo - pad, y_hi + pad),
+            log_x: self.log_x,
+        };
+        let (left, top, right, bottom) = (axes.left, axes.top, axes.left + axes.width, axes.top + axes.height);
+
+        writeln!(svg, r#"<text x="{}" y="{}" font-size="14" text-anchor="middle">{}</text>"#, (left + right) / 2.0, y0 + 20.0, escape(&panel.title)).unwrap();
+        writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black"/>"#, left, top, axes.width, axes.height).unwrap();
+
+        let x_ticks = if self.log_x { log_ticks(axes.x_range.0, axes.x_range.1) } else { linear_ticks(axes.x_range.0, axes.x_range.1) };
+        for t in x_ticks {
+            let x = axes.sx(t);
+            writeln!(svg, r##"<line x1="{x}" y1="{bottom}" x2="{x}" y2="{}" stroke="black"/><line x1="{x}" y1="{top}" x2="{x}" y2="{bottom}" stroke="#eeeeee"/>"##, bottom + 5.0).unwrap();
+            writeln!(svg, r#"<text x="{}" y="{}" font-size="11" text-anchor="middle">{}</text>"#, x, bottom + 18.0, format_tick(t)).unwrap();
+        }
+        for t in linear_ticks(axes.y_range.0, axes.y_range.1) {
+            let y = axes.sy(t);
+            writeln!(svg, r##"<line x1="{}" y1="{y}" x2="{left}" y2="{y}" stroke="black"/><line x1="{left}" y1="{y}" x2="{right}" y2="{y}" stroke="#eeeeee"/>"##, left - 5.0).unwrap();
+            writeln!(svg, r#"<text x="{}" y="{}" font-size="11" text-anchor="end">{}</text>"#, left - 8.0, y + 4.0, format_tick(t)).unwrap();
+        }
+        writeln!(svg, r#"<text x="{}" y="{}" font-size="12" text-anchor="middle">{}</text>"#, (left + right) / 2.0, bottom + 38.0, escape(&self.x_label)).unwrap();
+        writeln!(svg, r#"<text x="{x}" y="{y}" font-size="12" text-anchor="middle" transform="rotate(-90 {x} {y})">{}</text>"#, escape(&self.y_label), x = x0 + 16.0, y = (top + bottom) / 2.0).unwrap();
+
+        for series in &panel.series {
+            let color = COLORS[labels.iter().position(|&l| l == series.label).unwrap_or(0) % COLORS.len()];
+            let points = series.points
+                .iter()
+                .filter(|p| p.y.is_finite() && (!self.log_x || p.x > 0.0))
+                .copied()
+                .collect::<Vec<_>>();
+            let points = thin(&axes, &points);
+
+            if self.bands && points.len() > 1 {
+                let outline = points
+                    .iter()
+                    .map(|p| (p.x, p.high))
+                    .chain(points.iter().rev().map(|p| (p.x, p.low)))
+                    .map(|(x, y)| format!("{:.2},{:.2}", axes.sx(x), axes.sy(y)))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(svg, r#"<polygon points="{}" fill="{}" fill-opacity="0.15" stroke="none"/>"#, outline, color).unwrap();
+            }
+
+            let line = points
+                .iter()
+                .map(|p| format!("{:.2},{:.2}", axes.sx(p.x), axes.sy(p.y)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(svg, r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#, line, color).unwrap();
+        }
+
+        let legend_height = 16.0 * panel.series.len() as f64 + 8.0;
+        let legend_width = 42.0 + 6.5 * panel.series.iter().map(|s| s.label.chars().count()).max().unwrap_or(0) as f64;
+        writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="white" fill-opacity="0.8" stroke="#cccccc"/>"##, left + 8.0, top + 8.0, legend_width, legend_height).unwrap();
+        for (i, series) in panel.series.iter().enumerate() {
+            let color = COLORS[labels.iter().position(|&l| l == series.label).unwrap_or(0) % COLORS.len()];
+            let y = top + 22.0 + 16.0 * i as f64;
+            writeln!(svg, r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{}" stroke-width="3"/>"#, left + 14.0, left + 34.0, color).unwrap();
+            writeln!(svg, r#"<text x="{}" y="{}" font-size="11">{}</text>"#, left + 40.0, y + 4.0, escape(&series.label)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use benchmarks::{uniform, writes, BATCH};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use online::allocation::*;
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [16, 64, 256];
+const D: [usize; 2] = [16, 128];
+const WRITE_PROBABILITY: f64 = 0.1;
+
+fn allocation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("allocation/Count");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    for size in SIZES {
+        for d in D {
+            let requests = uniform(size, BATCH)
+                .into_iter()
+                .zip(writes(WRITE_PROBABILITY, BATCH))
+                .collect::<Vec<_>>();
+            let mut algorithm = Count::new(size, 0, d);
+
+            group.bench_with_input(BenchmarkId::new(format!("nodes_{}", size), d), &requests, |b, requests| {
+                b.iter(|| {
+                    requests
+                        .iter()
+                        .map(|&(source, write)| {
+                            if write {
+                                algorithm.write(black_box(source)).unwrap()
+                            } else {
+                                algorithm.read(black_box(source)).unwrap()
+                            }
+                        })
+                        .sum::<usize>()
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, allocation);
+criterion_main!(benches);
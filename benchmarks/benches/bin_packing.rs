@@ -0,0 +1,45 @@
+use benchmarks::items;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use online::bin_packing::*;
+use std::hint::black_box;
+
+type Pack = fn() -> Box<dyn Packer<f64>>;
+
+const PACKERS: [(&str, Pack); 5] = [
+    ("NF", || Box::new(NextFit::new())),
+    ("RF", || Box::new(RandomFit::new())),
+    ("FF", || Box::new(FirstFit::new())),
+    ("BF", || Box::new(BestFit::new())),
+    ("WF", || Box::new(WorstFit::new())),
+];
+
+// the number of open bins grows with the number of items packed
+const SIZES: [usize; 3] = [100, 1000, 10000];
+
+fn bin_packing(c: &mut Criterion) {
+    for (name, new) in PACKERS {
+        let mut group = c.benchmark_group(format!("bin_packing/{}", name));
+
+        for n in SIZES {
+            let items = items(n);
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(n), &items, |b, items| {
+                b.iter_batched(
+                    new,
+                    |mut packer| {
+                        for &item in items {
+                            packer.pack(black_box(item)).unwrap();
+                        }
+                        packer.no_bins()
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bin_packing);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use benchmarks::{harmonic, BATCH};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use online::dynlist::*;
+use online::indexed::*;
+use std::hint::black_box;
+
+type List = fn() -> Box<dyn Dynlist<usize>>;
+
+const LISTS: [(&str, List); 14] = [
+    ("Simple", || Box::new(SimpleList::new())),
+    ("TP", || Box::new(TPList::new())),
+    ("MTF", || Box::new(MTFList::new())),
+    ("FC", || Box::new(FCList::new())),
+    ("TS", || Box::new(TSList::new())),
+    ("BIT", || Box::new(BITList::with_seed(0))),
+    ("COMB", || Box::new(COMBList::with_seed(0))),
+    ("IndexedSimple", || Box::new(IndexedSimpleList::new())),
+    ("IndexedTP", || Box::new(IndexedTPList::new())),
+    ("IndexedMTF", || Box::new(IndexedMTFList::new())),
+    ("IndexedFC", || Box::new(IndexedFCList::new())),
+    ("IndexedTS", || Box::new(IndexedTSList::new())),
+    ("IndexedBIT", || Box::new(IndexedBITList::with_seed(0))),
+    ("IndexedCOMB", || Box::new(IndexedCOMBList::with_seed(0))),
+];
+
+const SIZES: [usize; 4] = [16, 64, 256, 1024];
+
+fn list_update(c: &mut Criterion) {
+    for (name, new) in LISTS {
+        let mut group = c.benchmark_group(format!("list_update/{}", name));
+        group.throughput(Throughput::Elements(BATCH as u64));
+
+        for n in SIZES {
+            let requests = harmonic(n, BATCH);
+            // start from a list that already holds every element
+            let mut list = new();
+            for &r in &requests {
+                list.access(r);
+            }
+
+            group.bench_with_input(BenchmarkId::from_parameter(n), &requests, |b, requests| {
+                b.iter(|| requests.iter().map(|&r| list.access(black_box(r))).sum::<usize>())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, list_update);
+criterion_main!(benches);
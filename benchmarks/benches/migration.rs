@@ -0,0 +1,46 @@
+use benchmarks::{uniform, BATCH};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use online::migration::*;
+use metricgraph::*;
+use std::hint::black_box;
+
+type Graph = fn() -> Box<dyn MetricGraph>;
+type Algorithm = fn(Box<dyn MetricGraph>, usize) -> Box<dyn PageMigration>;
+
+const GRAPHS: [(&str, Graph); 5] = [
+    ("torus3d_27", || Box::new(Torus::<3>::new(3))),
+    ("torus3d_64", || Box::new(Torus::<3>::new(4))),
+    ("torus3d_216", || Box::new(Torus::<3>::new(6))),
+    ("hypercube_64", || Box::new(Torus::<6>::new(2))),
+    ("hypercube_256", || Box::new(Torus::<8>::new(2))),
+];
+
+const ALGORITHMS: [(&str, Algorithm); 2] = [
+    ("MoveToMin", |graph, d| Box::new(MoveToMin::new(graph, 0, d))),
+    ("CoinFlip", |graph, d| Box::new(CoinFlip::new(graph, 0, d))),
+];
+
+const D: [usize; 2] = [16, 128];
+
+fn migration(c: &mut Criterion) {
+    for (name, new) in ALGORITHMS {
+        let mut group = c.benchmark_group(format!("migration/{}", name));
+        group.throughput(Throughput::Elements(BATCH as u64));
+
+        for (graph_name, graph) in GRAPHS {
+            for d in D {
+                let requests = uniform(graph().size(), BATCH);
+                let mut algorithm = new(graph(), d);
+
+                group.bench_with_input(BenchmarkId::new(graph_name, d), &requests, |b, requests| {
+                    b.iter(|| requests.iter().map(|&r| algorithm.read(black_box(r)).unwrap()).sum::<usize>())
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, migration);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use benchmarks::{harmonic, BATCH};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use online::paging::*;
+use std::hint::black_box;
+
+type Cache = fn(usize) -> Box<dyn Cacher<usize>>;
+
+const CACHES: [(&str, Cache); 6] = [
+    ("FIFO", |k| Box::new(FIFO::new(k))),
+    ("FWF", |k| Box::new(FWF::new(k))),
+    ("LRU", |k| Box::new(LRU::new(k))),
+    ("LFU", |k| Box::new(LFU::new(k))),
+    ("RAND", |k| Box::new(RAND::new(k))),
+    ("RMA", |k| Box::new(RMA::new(k))),
+];
+
+const SIZES: [usize; 4] = [8, 32, 128, 512];
+
+fn paging(c: &mut Criterion) {
+    for (name, new) in CACHES {
+        let mut group = c.benchmark_group(format!("paging/{}", name));
+        group.throughput(Throughput::Elements(BATCH as u64));
+
+        // pages come from a universe four times the cache size
+        for k in SIZES {
+            let requests = harmonic(4 * k, BATCH);
+            let mut cache = new(k);
+            for &r in &requests {
+                cache.access(r);
+            }
+
+            group.bench_with_input(BenchmarkId::from_parameter(k), &requests, |b, requests| {
+                b.iter(|| requests.iter().map(|&r| cache.access(black_box(r))).sum::<usize>())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, paging);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+use distributions::Generator;
+
+// request streams are fixed per size so that runs on different revisions see
+// the same work; the numbers are 0-based
+pub const SEED: u64 = 2024;
+
+// one batch of requests is replayed per iteration
+pub const BATCH: usize = 10000;
+
+pub fn uniform(n: usize, len: usize) -> Vec<usize> {
+    let mut g = Generator::with_seed(n, SEED);
+    (0..len).map(|_| g.uniform() - 1).collect()
+}
+
+pub fn harmonic(n: usize, len: usize) -> Vec<usize> {
+    let mut g = Generator::with_seed(n, SEED);
+    (0..len).map(|_| g.harmonic() - 1).collect()
+}
+
+pub fn items(len: usize) -> Vec<f64> {
+    let mut g = Generator::with_seed(1, SEED);
+    (0..len).map(|_| g.std()).collect()
+}
+
+pub fn writes(p: f64, len: usize) -> Vec<bool> {
+    let mut g = Generator::with_seed(1, SEED);
+    (0..len).map(|_| g.bernoulli(p) == 1).collect()
+}
@@ -1,10 +1,10 @@
 use rand::distributions::{Distribution, Uniform};
-use rand::rngs::ThreadRng;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 
 pub struct Generator {
     n: usize,
-    rng: ThreadRng,
+    rng: StdRng,
 
     std: Uniform<f64>,
     uniform: Uniform<usize>,
@@ -15,6 +15,14 @@ pub struct Generator {
 
 impl Generator {
     pub fn new(n: usize) -> Self {
+        Self::with_rng(n, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(n: usize, seed: u64) -> Self {
+        Self::with_rng(n, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(n: usize, rng: StdRng) -> Self {
         let harmonic_max = (1..=n).rev().fold(0.0, |acc, k| acc + 1.0 / k as f64);
         let mut harmonic_cdf = (2..=n).rev().fold(vec![harmonic_max], |mut v, k| {
             v.push(*v.last().unwrap() - 1.0 / k as f64);
@@ -31,7 +39,7 @@ impl Generator {
         
         Generator{
             n,
-            rng: thread_rng(),
+            rng,
             std: Uniform::new(0.0, 1.0),
             uniform: Uniform::new(1, n + 1),
             harmonic_cdf,
@@ -0,0 +1,17 @@
+use distributions::Generator;
+use online::dynlist::Dynlist;
+use runner::stats::Curve;
+
+// one repetition of the experiment, shared with the Python bindings: serves
+// `requests` elements drawn by `generate` and records the prefix cost
+pub fn repetition<L, F>(list: &mut L, g: &mut Generator, generate: F, requests: usize, cost: &mut Curve)
+where
+    L: Dynlist<usize> + ?Sized,
+    F: Fn(&mut Generator) -> usize,
+{
+    let mut compound_cost = 0;
+    for i in 0..requests {
+        compound_cost += list.access(generate(g));
+        cost.push(i, compound_cost as f64);
+    }
+}
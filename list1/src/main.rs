@@ -1,12 +1,14 @@
+#![allow(clippy::upper_case_acronyms)]
+
 use std::fmt;
-use std::fs::File;
 use std::io;
-use std::io::{Write, BufWriter};
 use distributions::Generator;
+use runner::output::Cell;
+use runner::{Context, Runner};
+use runner::stats::Samples;
 
-mod dynlist;
-
-use dynlist::*;
+use online::dynlist::Dynlist;
+use online::indexed::*;
 
 const N: usize = 100000;
 const REPS: usize = 100;
@@ -14,7 +16,7 @@ const NO_ELEMS: usize = 100;
 
 #[derive(Copy, Clone, PartialEq)]
 enum ListType {
-    Simple, TP, MTF, FC,
+    Simple, TP, MTF, FC, TS, BIT, COMB,
 }
 
 impl fmt::Display for ListType {
@@ -24,6 +26,9 @@ impl fmt::Display for ListType {
             ListType::TP => write!(f, "TP"),
             ListType::MTF => write!(f, "MTF"),
             ListType::FC => write!(f, "FC"),
+            ListType::TS => write!(f, "TS"),
+            ListType::BIT => write!(f, "BIT"),
+            ListType::COMB => write!(f, "COMB"),
         }
     }
 }
@@ -53,54 +58,48 @@ fn generate(g: &mut Generator, t: DataType) -> usize {
     }
 }
 
-fn measure(list_type: ListType, data_type: DataType, n: usize, reps: usize) -> Vec<f64> {
-    let mut g = Generator::new(NO_ELEMS);
+fn measure(list_type: ListType, data_type: DataType, elems: usize, n: usize, context: &mut Context) -> Samples {
+    // every list sees the same requests of the workload; only the coins of
+    // BIT and COMB depend on the list
+    let mut g = Generator::with_seed(elems, context.workload_seed());
 
-    let total_cost = (0..reps).fold(vec![0; n], |mut total_cost, _| {
+    let mut total_cost = context.curve(n);
+    while context.next_rep(&total_cost) {
+        // the indexed lists cost the same as the plain ones in dynlist, in
+        // O(log n) time per access, so long lists stay fast
         let mut list: Box<dyn Dynlist<usize>> = match list_type {
-            ListType::Simple => Box::new(SimpleList::new()),
-            ListType::TP => Box::new(TPList::new()),
-            ListType::MTF => Box::new(MTFList::new()),
-            ListType::FC => Box::new(FCList::new()),
+            ListType::Simple => Box::new(IndexedSimpleList::new()),
+            ListType::TP => Box::new(IndexedTPList::new()),
+            ListType::MTF => Box::new(IndexedMTFList::new()),
+            ListType::FC => Box::new(IndexedFCList::new()),
+            ListType::TS => Box::new(IndexedTSList::new()),
+            ListType::BIT => Box::new(IndexedBITList::with_seed(context.rep_seed())),
+            ListType::COMB => Box::new(IndexedCOMBList::with_seed(context.rep_seed())),
         };
 
-        let mut compound_cost = 0;
-
-        for i in 0..n {
-            let val = generate(&mut g, data_type);
-            let new_cost = list.access(val);
-            compound_cost += new_cost;
-            total_cost[i] += compound_cost;
-        }
-
-        total_cost
-    });
-
-    total_cost
-        .iter()
-        .map(|v| *v as f64 / reps as f64)
-        .collect()
-}
-
-fn write_vec_to_file<T: fmt::Display>(vec: Vec<T>, filename: &str) -> std::io::Result<()> {
-    let file = File::create(filename)?;
-    let mut writer = BufWriter::new(file);
-
-    for value in vec {
-        writeln!(writer, "{}", value)?;
+        list1::repetition(list.as_mut(), &mut g, |g| generate(g, data_type), n, &mut total_cost);
+        context.advance(n);
     }
 
-    Ok(())
+    total_cost.summary()
 }
 
 fn main() -> io::Result<()> {
-    for list_type in [ListType::Simple, ListType::TP, ListType::MTF, ListType::FC] {
+    let mut runner = Runner::new(runner::package!(), "list_update", &["elems"], &[("reps", REPS), ("requests", N), ("elems", NO_ELEMS)])?;
+
+    let mut grid = Vec::new();
+    for list_type in [ListType::Simple, ListType::TP, ListType::MTF, ListType::FC, ListType::TS, ListType::BIT, ListType::COMB] {
         for data_type in [DataType::Uniform, DataType::Harmonic, DataType::Biharmonic, DataType::Geometric] {
-            let cost = measure(list_type, data_type, N, REPS);
-            let filename = format!("results/result_{}_{}.txt", list_type, data_type);
-            write_vec_to_file(cost, &filename)?
+            grid.push((list_type, data_type));
         }
     }
+    let (reps, n, elems) = (runner.setting("reps"), runner.setting("requests"), runner.setting("elems"));
+    runner.plan(grid.len(), reps, n);
+
+    for (list_type, data_type) in grid {
+        let cell = Cell::new(list_type, data_type, vec![elems.to_string()]);
+        runner.run(cell, |context| vec![("cost", measure(list_type, data_type, elems, n, context))])?;
+    }
 
-    Ok(())
+    runner.finish()
 }
@@ -0,0 +1,17 @@
+use distributions::Generator;
+use online::paging::Cacher;
+use runner::stats::Curve;
+
+// one repetition of the experiment, shared with the Python bindings: serves
+// `requests` pages drawn by `generate` and records the prefix cost
+pub fn repetition<C, F>(cache: &mut C, g: &mut Generator, generate: F, requests: usize, cost: &mut Curve)
+where
+    C: Cacher<usize> + ?Sized,
+    F: Fn(&mut Generator) -> usize,
+{
+    let mut compound_cost = 0;
+    for i in 0..requests {
+        compound_cost += cache.access(generate(g));
+        cost.push(i, compound_cost as f64);
+    }
+}
@@ -1,13 +1,13 @@
+#![allow(clippy::upper_case_acronyms)]
+
 use distributions::Generator;
-use std::fs::File;
+use runner::output::Cell;
+use runner::{Context, Runner};
+use runner::stats::Samples;
 use std::fmt;
 use std::io;
-use std::io::BufWriter;
-use std::io::Write;
-
-mod paging;
 
-use paging::*;
+use online::paging::*;
 
 const REPS: usize = 10000;
 const REQUESTS: usize = 1000;
@@ -58,68 +58,47 @@ fn generate(g: &mut Generator, t: DataType) -> usize {
     }
 }
 
-fn measure(cache_type: CacheType, data_type: DataType, n: usize, k: usize, requests: usize, reps: usize) -> Vec<f64> {
-    let mut g = Generator::new(n);
+fn measure(cache_type: CacheType, data_type: DataType, n: usize, k: usize, requests: usize, context: &mut Context) -> Samples {
+    let mut g = Generator::with_seed(n, context.seed());
 
-    let total_cost = (0..reps).fold(vec![0; requests], |mut total_cost, _| {
+    let mut total_cost = context.curve(requests);
+    while context.next_rep(&total_cost) {
         let mut cache: Box<dyn Cacher<usize>> = match cache_type {
             CacheType::FIFO => Box::new(FIFO::new(k)),
             CacheType::FWF => Box::new(FWF::new(k)),
             CacheType::LRU => Box::new(LRU::new(k)),
             CacheType::LFU => Box::new(LFU::new(k)),
-            CacheType::RAND => Box::new(RAND::new(k)),
-            CacheType::RMA => Box::new(RMA::new(k)),
+            CacheType::RAND => Box::new(RAND::with_seed(k, context.rep_seed())),
+            CacheType::RMA => Box::new(RMA::with_seed(k, context.rep_seed())),
         };
 
-        let mut compound_cost = 0;
-
-        for i in 0..requests {
-            let val = generate(&mut g, data_type);
-            let new_cost = cache.access(val);
-            compound_cost += new_cost;
-            total_cost[i] += compound_cost;
-        }
-
-        total_cost
-    });
-
-    total_cost
-        .iter()
-        .map(|v| *v as f64 / reps as f64)
-        .collect()
-}
-
-fn write_vec_to_file<T: fmt::Display>(vec: Vec<T>, filename: &str) -> std::io::Result<()> {
-    let file = File::create(filename)?;
-    let mut writer = BufWriter::new(file);
-
-    for value in vec {
-        writeln!(writer, "{}", value)?;
+        list2::repetition(cache.as_mut(), &mut g, |g| generate(g, data_type), requests, &mut total_cost);
+        context.advance(requests);
     }
 
-    Ok(())
+    total_cost.summary()
 }
 
 fn main() -> io::Result<()> {
-    let mut progress = 1;
-    let no_experiments = 6 * 4;
+    let mut runner = Runner::new(runner::package!(), "paging", &["n", "k"], &[("reps", REPS), ("requests", REQUESTS)])?;
 
+    let mut grid = Vec::new();
     for cache_type in [CacheType::FIFO, CacheType::FWF, CacheType::LRU, CacheType::LFU, CacheType::RAND, CacheType::RMA] {
         for data_type in [DataType::Uniform, DataType::Harmonic, DataType::Biharmonic, DataType::Geometric] {
-            print!("\rProgress: {}/{}", progress, no_experiments);
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-
             for n in N {
                 for k in (n / K_RANGE[0])..=(n / K_RANGE[1]) {
-                    let cost = measure(cache_type, data_type, n, k, REQUESTS, REPS);
-                    let filename = format!("results/result_{}_{}_{}_{}.txt", cache_type, data_type, n, k);
-                    write_vec_to_file(cost, &filename)?
+                    grid.push((cache_type, data_type, n, k));
                 }
             }
-            progress += 1;
         }
     }
+    let (reps, requests) = (runner.setting("reps"), runner.setting("requests"));
+    runner.plan(grid.len(), reps, requests);
+
+    for (cache_type, data_type, n, k) in grid {
+        let cell = Cell::new(cache_type, data_type, vec![n.to_string(), k.to_string()]);
+        runner.run(cell, |context| vec![("cost", measure(cache_type, data_type, n, k, requests, context))])?;
+    }
 
-    println!();
-    Ok(())
+    runner.finish()
 }
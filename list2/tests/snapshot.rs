@@ -0,0 +1,12 @@
+use std::path::Path;
+
+// a small run with a fixed seed; UPDATE_SNAPSHOTS=1 accepts a changed output
+#[test]
+fn small_run_matches_snapshot() {
+    let snapshot = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/results.csv");
+    runner::snapshot::check(
+        env!("CARGO_BIN_EXE_list2"),
+        &["--seed", "2024", "--set", "reps=3", "--set", "requests=100", "--sample", "final"],
+        &snapshot,
+    );
+}
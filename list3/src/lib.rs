@@ -0,0 +1,26 @@
+use distributions::Generator;
+use online::bin_packing::Packer;
+use runner::stats::Curve;
+
+// one repetition of the experiment, shared with the Python bindings: packs
+// `items` items in runs of equal sizes, with run lengths drawn by `generate`,
+// and records the bins used over the lower bound ceil(sum of sizes)
+pub fn repetition<P, F>(packer: &mut P, g: &mut Generator, generate: F, items: usize, ratio: &mut Curve)
+where
+    P: Packer<f64> + ?Sized,
+    F: Fn(&mut Generator) -> usize,
+{
+    let mut item = 0.0;
+    let mut items_remaining = 0;
+    let mut item_sum = 0.0;
+    for i in 0..items {
+        if items_remaining == 0 {
+            items_remaining = generate(g);
+            item = g.std();
+        }
+        item_sum += item;
+        items_remaining -= 1;
+        packer.pack(item).unwrap();
+        ratio.push(i, packer.no_bins() as f64 / item_sum.ceil());
+    }
+}
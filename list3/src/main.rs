@@ -1,16 +1,15 @@
 use distributions::Generator;
-use std::fs::File;
+use runner::output::Cell;
+use runner::{Context, Runner};
+use runner::stats::Samples;
 use std::fmt;
 use std::io;
-use std::io::BufWriter;
-use std::io::Write;
 
-mod bin_packing;
-
-use bin_packing::*;
+use online::bin_packing::*;
 
 const REPS: usize = 10000;
 const NO_ITEMS: usize = 1000;
+const MAX_RUN: usize = 10;
 
 #[derive(Copy, Clone, PartialEq)]
 enum PackerType {
@@ -54,62 +53,42 @@ fn generate(g: &mut Generator, t: DataType) -> usize {
     }
 }
 
-fn measure(packer_type: PackerType, data_type: DataType, n: usize, reps: usize) -> Vec<f64> {
-    let mut g = Generator::new(10);
+fn measure(packer_type: PackerType, data_type: DataType, n: usize, context: &mut Context) -> Samples {
+    let mut g = Generator::with_seed(MAX_RUN, context.seed());
 
-    let total_competitiveness = (0..reps).fold(vec![0.0; n], |mut total_competitiveness, _| {
+    let mut total_competitiveness = context.curve(n);
+    while context.next_rep(&total_competitiveness) {
         let mut packer: Box<dyn Packer<f64>> = match packer_type {
             PackerType::NF => Box::new(NextFit::new()),
-            PackerType::RF => Box::new(RandomFit::new()),
+            PackerType::RF => Box::new(RandomFit::with_seed(context.rep_seed())),
             PackerType::FF => Box::new(FirstFit::new()),
             PackerType::BF => Box::new(BestFit::new()),
             PackerType::WF => Box::new(WorstFit::new()),
         };
 
-        let mut item = 0.0;
-        let mut items_remaining = 0;
-        let mut item_sum = 0.0;
-        for total_competitiveness_i in total_competitiveness.iter_mut() {
-            if items_remaining == 0 {
-                items_remaining = generate(&mut g, data_type);
-                item = g.std();
-            }
-            item_sum += item;
-            items_remaining -= 1;
-            packer.pack(item).unwrap();
-            *total_competitiveness_i += packer.no_bins() as f64 / item_sum.ceil();
-        }
-
-        total_competitiveness
-    });
-
-    total_competitiveness
-        .iter()
-        .map(|v| *v as f64 / reps as f64)
-        .collect()
-}
-
-fn write_vec_to_file<T: fmt::Display>(vec: Vec<T>, filename: &str) -> std::io::Result<()> {
-    let file = File::create(filename)?;
-    let mut writer = BufWriter::new(file);
-
-    for value in vec {
-        writeln!(writer, "{}", value)?;
+        list3::repetition(packer.as_mut(), &mut g, |g| generate(g, data_type), n, &mut total_competitiveness);
+        context.advance(n);
     }
 
-    Ok(())
+    total_competitiveness.summary()
 }
 
 fn main() -> io::Result<()> {
+    let mut runner = Runner::new(runner::package!(), "bin_packing", &["max_run"], &[("reps", REPS), ("items", NO_ITEMS)])?;
+
+    let mut grid = Vec::new();
     for packer_type in [PackerType::NF, PackerType::RF, PackerType::FF, PackerType::BF, PackerType::WF] {
         for data_type in [DataType::Uniform, DataType::Harmonic, DataType::Biharmonic, DataType::Geometric] {
-            println!("{}, {}", packer_type, data_type);
-            let cost = measure(packer_type, data_type, NO_ITEMS, REPS);
-            let filename = format!("results/result_{}_{}.txt", packer_type, data_type);
-            write_vec_to_file(cost, &filename)?
+            grid.push((packer_type, data_type));
         }
     }
+    let (reps, items) = (runner.setting("reps"), runner.setting("items"));
+    runner.plan(grid.len(), reps, items);
 
-    Ok(())
-}
+    for (packer_type, data_type) in grid {
+        let cell = Cell::new(packer_type, data_type, vec![MAX_RUN.to_string()]);
+        runner.run(cell, |context| vec![("ratio", measure(packer_type, data_type, items, context))])?;
+    }
 
+    runner.finish()
+}
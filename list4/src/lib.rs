@@ -0,0 +1,18 @@
+use distributions::Generator;
+use online::migration::PageMigration;
+use runner::stats::Curve;
+
+// one repetition of the experiment, shared with the Python bindings: serves
+// reads from the 1-based nodes drawn by `generate` and records the prefix cost
+pub fn repetition<M, F>(algorithm: &mut M, g: &mut Generator, generate: F, requests: usize, cost: &mut Curve)
+where
+    M: PageMigration + ?Sized,
+    F: Fn(&mut Generator) -> usize,
+{
+    let mut compound_cost = 0;
+    for i in 0..requests {
+        let source = generate(g) - 1;
+        compound_cost += algorithm.read(source).unwrap();
+        cost.push(i, compound_cost as f64);
+    }
+}
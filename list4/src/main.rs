@@ -1,13 +1,12 @@
 use distributions::Generator;
+use runner::output::Cell;
+use runner::{Context, Runner};
+use runner::stats::Samples;
 use metricgraph::*;
-use std::fs::File;
 use std::fmt;
 use std::io;
-use std::io::BufWriter;
-use std::io::Write;
 
-mod migration;
-use migration::*;
+use online::migration::*;
 
 const REPS: usize = 100;
 const NO_REQUESTS: usize = 65536;
@@ -63,10 +62,11 @@ fn generate(g: &mut Generator, t: DataType) -> usize {
     }
 }
 
-fn measure(algorithm_type: AlgorithmType, graph_type: GraphType, data_type: DataType, d: usize, requests: usize, reps: usize) -> Vec<f64> {
-    let mut g = Generator::new(64);
+fn measure(algorithm_type: AlgorithmType, graph_type: GraphType, data_type: DataType, d: usize, requests: usize, context: &mut Context) -> Samples {
+    let mut g = Generator::with_seed(64, context.seed());
 
-    let total_cost = (0..reps).fold(vec![0; requests], |mut total_cost, _| {
+    let mut total_cost = context.curve(requests);
+    while context.next_rep(&total_cost) {
         let graph: Box<dyn MetricGraph> = match graph_type {
             GraphType::Torus3D => Box::new(Torus::<3>::new(4)),
             GraphType::Hypercube => Box::new(Torus::<6>::new(2)),
@@ -74,52 +74,36 @@ fn measure(algorithm_type: AlgorithmType, graph_type: GraphType, data_type: Data
 
         let mut algorithm: Box<dyn PageMigration> = match algorithm_type {
             AlgorithmType::MoveToMin => Box::new(MoveToMin::new(graph, 0, d)),
-            AlgorithmType::CoinFlip => Box::new(CoinFlip::new(graph, 0, d)),
+            AlgorithmType::CoinFlip => Box::new(CoinFlip::with_seed(graph, 0, d, context.rep_seed())),
         };
 
-        let mut compound_cost = 0;
-        for i in 0..requests {
-            let source = generate(&mut g, data_type) - 1;
-            compound_cost += algorithm.read(source).unwrap();
-            total_cost[i] += compound_cost;
-        }
-
-        total_cost
-    });
-
-    total_cost
-        .iter()
-        .map(|v| *v as f64 / reps as f64)
-        .collect()
-}
-
-fn write_vec_to_file<T: fmt::Display>(vec: Vec<T>, filename: &str) -> std::io::Result<()> {
-    let file = File::create(filename)?;
-    let mut writer = BufWriter::new(file);
-
-    for value in vec {
-        writeln!(writer, "{}", value)?;
+        list4::repetition(algorithm.as_mut(), &mut g, |g| generate(g, data_type), requests, &mut total_cost);
+        context.advance(requests);
     }
 
-    Ok(())
+    total_cost.summary()
 }
 
 fn main() -> io::Result<()> {
+    let mut runner = Runner::new(runner::package!(), "page_migration", &["graph", "d"], &[("reps", REPS), ("requests", NO_REQUESTS)])?;
+
+    let mut grid = Vec::new();
     for algorithm_type in [AlgorithmType::MoveToMin, AlgorithmType::CoinFlip] {
         for graph_type in [GraphType::Torus3D, GraphType::Hypercube] {
             for data_type in [DataType::Uniform, DataType::Harmonic, DataType::Biharmonic] {
                 for d in [2, 16, 128, 2048] {
-                    println!("{}, {}, {}, {}", algorithm_type, graph_type, data_type, d);
-                    let cost = measure(algorithm_type, graph_type, data_type, d, NO_REQUESTS, REPS);
-                    let filename = format!("results/result_{}_{}_{}_{}.txt", algorithm_type, graph_type, data_type, d);
-                    write_vec_to_file(cost, &filename)?
-
+                    grid.push((algorithm_type, graph_type, data_type, d));
                 }
             }
         }
     }
+    let (reps, requests) = (runner.setting("reps"), runner.setting("requests"));
+    runner.plan(grid.len(), reps, requests);
 
-    Ok(())
-}
-
+    for (algorithm_type, graph_type, data_type, d) in grid {
+        let cell = Cell::new(algorithm_type, data_type, vec![graph_type.to_string(), d.to_string()]);
+        runner.run(cell, |context| vec![("cost", measure(algorithm_type, graph_type, data_type, d, requests, context))])?;
+    }
 
+    runner.finish()
+}
@@ -0,0 +1,22 @@
+use distributions::Generator;
+use online::allocation::{Count, PageAllocation};
+use runner::stats::Curve;
+
+// one repetition of the experiment, shared with the Python bindings: serves
+// requests from the 1-based nodes drawn by `generate`, each a write with
+// probability `p`, and records the prefix cost and the number of pages
+pub fn repetition<F>(algorithm: &mut Count, g: &mut Generator, generate: F, p: f64, requests: usize, cost: &mut Curve, pages: &mut Curve)
+where F: Fn(&mut Generator) -> usize
+{
+    let mut compound_cost = 0;
+    for i in 0..requests {
+        let source = generate(g) - 1;
+        if g.bernoulli(p) == 1 {
+            compound_cost += algorithm.write(source).unwrap();
+        } else {
+            compound_cost += algorithm.read(source).unwrap();
+        }
+        cost.push(i, compound_cost as f64);
+        pages.push(i, algorithm.no_pages() as f64);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::events::Observer;
+
+// what every algorithm in the crate has in common: it serves one request at a
+// time at some cost, may reject an invalid request, and can start over
+pub trait OnlineAlgorithm {
+    type Request;
+    type Cost;
+    type Error;
+
+    fn serve_observed(&mut self, request: Self::Request, observer: &mut dyn Observer) -> Result<Self::Cost, Self::Error>;
+
+    fn serve(&mut self, request: Self::Request) -> Result<Self::Cost, Self::Error> {
+        self.serve_observed(request, &mut ())
+    }
+
+    // back to the initial configuration; randomized algorithms keep drawing
+    // from their generator, so repeated runs are independent
+    fn reset(&mut self);
+
+    // validates the internal state, describing the first inconsistency found
+    fn check_invariants(&self) -> Result<(), String>;
+}
+
+// fails if two of the items are equal, naming their 0-based positions
+pub fn distinct<'a, T: Eq + 'a>(items: impl Iterator<Item = &'a T>) -> Result<(), String> {
+    let items = items.collect::<Vec<_>>();
+    for (i, a) in items.iter().enumerate() {
+        if let Some(j) = items[i + 1..].iter().position(|b| a == b) {
+            return Err(format!("positions {} and {} hold the same item", i, i + 1 + j));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::algorithm::OnlineAlgorithm;
+use crate::events::{Event, Observer};
+use std::collections::HashSet;
+use std::fmt;
+use metricgraph::*;
+
+pub trait PageAllocation {
+    fn read_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr>;
+    fn write_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr>;
+
+    fn read(&mut self, source: usize) -> Result<usize, IndexErr> {
+        self.read_observed(source, &mut ())
+    }
+
+    fn write(&mut self, source: usize) -> Result<usize, IndexErr> {
+        self.write_observed(source, &mut ())
+    }
+}
+
+// a request to the allocated page, made at a node
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Access {
+    Read(usize),
+    Write(usize),
+}
+
+impl Access {
+    pub fn node(&self) -> usize {
+        match *self {
+            Access::Read(node) | Access::Write(node) => node,
+        }
+    }
+}
+
+// "r3" reads at node 3, "w3" writes there
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Read(node) => write!(f, "r{}", node),
+            Access::Write(node) => write!(f, "w{}", node),
+        }
+    }
+}
+
+pub struct Count {
+    size: usize,
+    start: usize,
+    pub pages: HashSet<usize>,
+    d: usize,
+    pub states: Vec<u8>,
+    pub counters: Vec<usize>,
+}
+
+impl Count {
+    pub fn new(size: usize, page: usize, d: usize) -> Self {
+        let mut pages = HashSet::new();
+        pages.insert(page);
+        let mut states = vec![1; size];
+        states[page] = 4;
+        let counters = vec![0; size];
+        Self {
+            size,
+            start: page,
+            pages,
+            d,
+            states,
+            counters,
+        }
+    }
+
+    pub fn no_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn drop_pages(&mut self, observer: &mut dyn Observer) {
+        for i in 0..self.size {
+            if self.states[i] == 4 && self.pages.len() > 1 {
+                self.states[i] = 1;
+                self.pages.remove(&i);
+                observer.event(Event::Drop { node: i });
+            }
+        }
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut replicas = self.pages.iter().collect::<Vec<_>>();
+        replicas.sort();
+        write!(f, "replicas {:?}, states {:?}, counters {:?}", replicas, self.states, self.counters)
+    }
+}
+
+impl PageAllocation for Count {
+    fn read_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let cost = if self.pages.contains(&source) {
+            observer.event(Event::Hit { item: source });
+            0
+        } else {
+            observer.event(Event::Miss { item: source });
+            1
+        };
+
+        if self.states[source] == 1 {
+            self.counters[source] += 1;
+            if self.counters[source] == self.d {
+                self.pages.insert(source);
+                self.states[source] = 3;
+                observer.event(Event::Replicate { node: source });
+                self.drop_pages(observer);
+            }
+        }
+
+        Ok(cost)
+    }
+
+    fn write_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let cost = if self.pages.contains(&source) {
+            self.pages.len() - 1
+        } else {
+            self.pages.len()
+        };
+
+        for i in 0..self.size {
+            if i != source && self.states[i] == 3 {
+                self.counters[i] -= 1;
+                if self.counters[i] == 0 {
+                    self.states[i] = 4;
+                }
+            }
+        }
+
+        if self.states[source] == 1 {
+            self.counters[source] += 1;
+            if self.counters[source] == self.d {
+                self.pages.insert(source);
+                self.states[source] = 3;
+                observer.event(Event::Replicate { node: source });
+            }
+        }
+        
+        self.drop_pages(observer);
+
+        Ok(cost)
+    }
+}
+
+impl OnlineAlgorithm for Count {
+    type Request = Access;
+    type Cost = usize;
+    type Error = IndexErr;
+
+    fn serve_observed(&mut self, access: Access, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        match access {
+            Access::Read(source) => self.read_observed(source, observer),
+            Access::Write(source) => self.write_observed(source, observer),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Count::new(self.size, self.start, self.d);
+    }
+
+    // a node holds a replica exactly when it is in state 3 or 4, and a node in
+    // state 1 replicates once its counter reaches d
+    fn check_invariants(&self) -> Result<(), String> {
+        if self.pages.is_empty() {
+            return Err("no node holds the page".to_string());
+        }
+        if let Some(node) = self.pages.iter().find(|&&node| node >= self.size) {
+            return Err(format!("replica at node {} outside the {} nodes", node, self.size));
+        }
+        if self.states.len() != self.size || self.counters.len() != self.size {
+            return Err(format!("{} states and {} counters for {} nodes", self.states.len(), self.counters.len(), self.size));
+        }
+        for node in 0..self.size {
+            let state = self.states[node];
+            let replica = self.pages.contains(&node);
+            match state {
+                1 if replica => return Err(format!("node {} holds a replica in state 1", node)),
+                3 | 4 if !replica => return Err(format!("node {} is in state {} without a replica", node, state)),
+                1 if self.counters[node] >= self.d => {
+                    return Err(format!("node {} has counter {} without replicating, d = {}", node, self.counters[node], self.d));
+                }
+                1 | 3 | 4 => {}
+                _ => return Err(format!("node {} is in unknown state {}", node, state)),
+            }
+            if self.counters[node] > self.d {
+                return Err(format!("node {} has counter {} above d = {}", node, self.counters[node], self.d));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+use crate::algorithm::OnlineAlgorithm;
+use crate::events::{Event, Observer};
+use crate::tape::{Coins, Randomized};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PackError {
+    InvalidSize,
+}
+
+pub trait Packer<T> {
+    fn pack_observed(&mut self, item: T, observer: &mut dyn Observer) -> Result<(), PackError>;
+    fn no_bins(&self) -> usize;
+
+    fn pack(&mut self, item: T) -> Result<(), PackError> {
+        self.pack_observed(item, &mut ())
+    }
+}
+
+// the cost of an item is the number of bins it opens; `$reset` empties them
+// and `$check` validates them
+macro_rules! impl_online {
+    ($name: ident, $reset: expr, $check: expr) => {
+        impl OnlineAlgorithm for $name {
+            type Request = f64;
+            type Cost = usize;
+            type Error = PackError;
+
+            fn serve_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<usize, PackError> {
+                let before = self.no_bins();
+                self.pack_observed(item, observer)?;
+                Ok(self.no_bins() - before)
+            }
+
+            fn reset(&mut self) {
+                let reset: fn(&mut Self) = $reset;
+                reset(self)
+            }
+
+            fn check_invariants(&self) -> Result<(), String> {
+                let check: fn(&Self) -> Result<(), String> = $check;
+                check(self)
+            }
+        }
+    }
+}
+
+// every bin's free space lies in [0, 1]
+fn residuals(bins: &[f64]) -> Result<(), String> {
+    match bins.iter().position(|b| !(0.0..=1.0).contains(b)) {
+        Some(i) => Err(format!("bin {} has free space {}", i, bins[i])),
+        None => Ok(()),
+    }
+}
+
+fn show_bins(f: &mut fmt::Formatter<'_>, bins: &[f64]) -> fmt::Result {
+    let residuals = bins.iter().map(|b| format!("{:.3}", b)).collect::<Vec<_>>();
+    write!(f, "{} bins, free space [{}]", bins.len(), residuals.join(", "))
+}
+
+// puts an item into the chosen bin, or into a new one at the end
+fn place(bins: &mut Vec<f64>, chosen: Option<usize>, item: f64, observer: &mut dyn Observer) {
+    let bin = match chosen {
+        Some(bin) => {
+            bins[bin] -= item;
+            bin
+        }
+        None => {
+            bins.push(1.0 - item);
+            observer.event(Event::OpenBin { bin: bins.len() - 1 });
+            bins.len() - 1
+        }
+    };
+    observer.event(Event::Pack { bin, size: item });
+}
+
+pub struct NextFit {
+    no_bins: usize,
+    bin_space: f64,
+}
+
+impl NextFit {
+    pub fn new() -> Self {
+        Self {
+            no_bins: 0,
+            bin_space: 0.0,
+        }
+    }
+}
+
+impl Default for NextFit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for NextFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bins, free space in the open one {:.3}", self.no_bins, self.bin_space)
+    }
+}
+
+impl Packer<f64> for NextFit {
+    fn pack_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<(), PackError> {
+        if !(0.0..=1.0).contains(&item) {
+            return Err(PackError::InvalidSize);
+        }
+
+        if self.bin_space < item {
+            self.no_bins += 1;
+            self.bin_space = 1.0;
+            observer.event(Event::OpenBin { bin: self.no_bins - 1 });
+        }
+
+        self.bin_space -= item;
+        observer.event(Event::Pack { bin: self.no_bins - 1, size: item });
+
+        Ok(())
+    }
+
+    fn no_bins(&self) -> usize {
+        self.no_bins
+    }
+}
+
+impl_online!(NextFit, |nf| *nf = NextFit::new(), |nf| {
+    if nf.no_bins == 0 && nf.bin_space != 0.0 {
+        return Err(format!("no bins are open but one has free space {}", nf.bin_space));
+    }
+    residuals(&[nf.bin_space])
+});
+
+pub struct RandomFit {
+    bins: Vec<f64>,
+    coins: Coins,
+}
+
+impl RandomFit {
+    pub fn new() -> Self {
+        Self::with_coins(Coins::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_coins(Coins::seeded(seed))
+    }
+
+    pub fn with_coins(coins: Coins) -> Self {
+        Self {
+            bins: Vec::new(),
+            coins,
+        }
+    }
+}
+
+impl Randomized for RandomFit {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+impl Default for RandomFit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for RandomFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        show_bins(f, &self.bins)
+    }
+}
+
+impl Packer<f64> for RandomFit {
+    fn pack_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<(), PackError> {
+        if !(0.0..=1.0).contains(&item) {
+            return Err(PackError::InvalidSize);
+        }
+
+        let available_bins: Vec<usize> = (0..self.bins.len())
+            .filter(|&i| self.bins[i] >= item)
+            .collect();
+
+        let chosen_bin = match available_bins.len() {
+            0 => None,
+            n => Some(available_bins[self.coins.index(n)]),
+        };
+        place(&mut self.bins, chosen_bin, item, observer);
+
+        Ok(())
+    }
+
+    fn no_bins(&self) -> usize {
+        self.bins.len()
+    }
+}
+
+impl_online!(RandomFit, |rf| rf.bins.clear(), |rf| residuals(&rf.bins));
+
+pub struct FirstFit {
+    bins: Vec<f64>,
+}
+
+impl FirstFit {
+    pub fn new() -> Self {
+        Self {
+            bins: Vec::new(),
+        }
+    }
+}
+
+impl Default for FirstFit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for FirstFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        show_bins(f, &self.bins)
+    }
+}
+
+impl Packer<f64> for FirstFit {
+    fn pack_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<(), PackError> {
+        if !(0.0..=1.0).contains(&item) {
+            return Err(PackError::InvalidSize);
+        }
+
+        let chosen_bin = self.bins
+            .iter()
+            .position(|&b| b >= item);
+
+        place(&mut self.bins, chosen_bin, item, observer);
+
+        Ok(())
+    }
+
+    fn no_bins(&self) -> usize {
+        self.bins.len()
+    }
+}
+
+impl_online!(FirstFit, |ff| ff.bins.clear(), |ff| residuals(&ff.bins));
+
+pub struct BestFit {
+    bins: Vec<f64>,
+}
+
+impl BestFit {
+    pub fn new() -> Self {
+        Self {
+            bins: Vec::new(),
+        }
+    }
+}
+
+impl Default for BestFit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for BestFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        show_bins(f, &self.bins)
+    }
+}
+
+impl Packer<f64> for BestFit {
+    fn pack_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<(), PackError> {
+        if !(0.0..=1.0).contains(&item) {
+            return Err(PackError::InvalidSize);
+        }
+
+        let chosen_bin = self.bins
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b >= item)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+
+        place(&mut self.bins, chosen_bin, item, observer);
+
+        Ok(())
+    }
+
+    fn no_bins(&self) -> usize {
+        self.bins.len()
+    }
+}
+
+impl_online!(BestFit, |bf| bf.bins.clear(), |bf| residuals(&bf.bins));
+
+pub struct WorstFit {
+    bins: Vec<f64>,
+}
+
+impl WorstFit {
+    pub fn new() -> Self {
+        Self {
+            bins: Vec::new(),
+        }
+    }
+}
+
+impl Default for WorstFit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for WorstFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        show_bins(f, &self.bins)
+    }
+}
+
+impl Packer<f64> for WorstFit {
+    fn pack_observed(&mut self, item: f64, observer: &mut dyn Observer) -> Result<(), PackError> {
+        if !(0.0..=1.0).contains(&item) {
+            return Err(PackError::InvalidSize);
+        }
+
+        let chosen_bin = self.bins
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b >= item)
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i);
+
+        place(&mut self.bins, chosen_bin, item, observer);
+
+        Ok(())
+    }
+
+    fn no_bins(&self) -> usize {
+        self.bins.len()
+    }
+}
+
+impl_online!(WorstFit, |wf| wf.bins.clear(), |wf| residuals(&wf.bins));
@@ -0,0 +1,382 @@
+use crate::algorithm::{distinct, OnlineAlgorithm};
+use crate::events::{Event, Observer};
+use crate::tape::{Coins, Randomized};
+use std::convert::Infallible;
+use std::fmt;
+
+pub trait Dynlist<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize;
+
+    fn access(&mut self, t: T) -> usize {
+        self.access_observed(t, &mut ())
+    }
+}
+
+// `$check` validates the list beyond its elements being distinct; any further
+// fields are listed with their initial values
+macro_rules! impl_list {
+    ($name: ident, $check: expr $(, $field: ident: $init: expr)*) => {
+        impl<T> $name<T> {
+            pub fn new() -> Self {
+                Self{v: Vec::new() $(, $field: $init)*}
+            }
+        }
+
+        impl<T> Default for $name<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl_list!(@algorithm $name, $check $(, $field: $init)*);
+    };
+    // without constructors, for lists that keep their coins across resets
+    (@algorithm $name: ident, $check: expr $(, $field: ident: $init: expr)*) => {
+        impl<T: Eq> OnlineAlgorithm for $name<T> {
+            type Request = T;
+            type Cost = usize;
+            type Error = Infallible;
+
+            fn serve_observed(&mut self, t: T, observer: &mut dyn Observer) -> Result<usize, Infallible> {
+                Ok(self.access_observed(t, observer))
+            }
+
+            fn reset(&mut self) {
+                self.v.clear();
+                $(self.$field = $init;)*
+            }
+
+            fn check_invariants(&self) -> Result<(), String> {
+                let check: fn(&Self) -> Result<(), String> = $check;
+                check(self)
+            }
+        }
+
+        impl<T: std::fmt::Debug> fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:?}", self.v)
+            }
+        }
+    }
+}
+
+pub struct SimpleList<T> {
+    v: Vec<T>,
+}
+
+impl_list!(SimpleList, |list| distinct(list.v.iter()));
+
+impl<T> Dynlist<T> for SimpleList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.v.iter().position(|v| *v == t) {
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                i + 1
+            }
+            None => {
+                self.v.push(t);
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+pub struct MTFList<T> {
+    v: Vec<T>,
+}
+
+impl_list!(MTFList, |list| distinct(list.v.iter()));
+
+impl<T> Dynlist<T> for MTFList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.v.iter().position(|v| *v == t) {
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                let item = self.v.remove(i);
+                self.v.insert(0, item);
+                if i > 0 {
+                    observer.event(Event::Move { from: i + 1, to: 1 });
+                }
+                i + 1
+            }
+            None => {
+                self.v.push(t);
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+pub struct TPList<T> {
+    v: Vec<T>,
+}
+
+impl_list!(TPList, |list| distinct(list.v.iter()));
+
+impl<T> Dynlist<T> for TPList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.v.iter().position(|v| *v == t) {
+            Some(i) if i > 0 => {
+                observer.event(Event::Hit { item: i + 1 });
+                self.v.swap(i - 1, i);
+                observer.event(Event::Move { from: i + 1, to: i });
+                i + 1
+            }
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                i + 1
+            }
+            None => {
+                self.v.push(t);
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+pub struct FCList<T> {
+    v: Vec<(T, usize)>,
+}
+
+// elements are kept in order of decreasing access count
+impl_list!(FCList, |list| {
+    distinct(list.v.iter().map(|(t, _)| t))?;
+    if let Some(i) = list.v.iter().position(|&(_, count)| count == 0) {
+        return Err(format!("element at position {} was never accessed", i));
+    }
+    match list.v.windows(2).position(|w| w[0].1 < w[1].1) {
+        Some(i) => Err(format!("counts increase from position {} ({}) to {} ({})", i, list.v[i].1, i + 1, list.v[i + 1].1)),
+        None => Ok(()),
+    }
+});
+
+impl<T> Dynlist<T> for FCList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.v.iter().position(|v| v.0 == t) {
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                self.v[i].1 += 1;
+                let pos = self.v[..i]
+                    .iter()
+                    .position(|(_, count)| *count < self.v[i].1);
+                if let Some(j) = pos {
+                    let item = self.v.remove(i);
+                    self.v.insert(j, item);
+                    observer.event(Event::Move { from: i + 1, to: j + 1 });
+                }
+                i + 1
+            }
+            None => {
+                self.v.push((t, 1));
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+// Albers' TIMESTAMP: an accessed element goes in front of the first element
+// before it that was requested at most once since the element's own last
+// request, and stays put if there is none. Each element keeps the times of its
+// last two requests
+pub struct TSList<T> {
+    v: Vec<(T, usize, Option<usize>)>,
+    time: usize,
+}
+
+impl_list!(TSList, |list| {
+    distinct(list.v.iter().map(|(t, _, _)| t))?;
+    for (i, &(_, last, before)) in list.v.iter().enumerate() {
+        if last >= list.time || before.is_some_and(|before| before >= last) {
+            return Err(format!("element at position {} has request times {:?} and {} at time {}", i, before, last, list.time));
+        }
+    }
+    Ok(())
+}, time: 0);
+
+impl<T> Dynlist<T> for TSList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        let now = self.time;
+        self.time += 1;
+        match self.v.iter().position(|v| v.0 == t) {
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                let last = self.v[i].1;
+                // requested at most once since `last` means the request before
+                // the most recent one came earlier
+                let pos = self.v[..i]
+                    .iter()
+                    .position(|(_, _, before)| before.is_none_or(|before| before < last));
+                self.v[i].2 = Some(last);
+                self.v[i].1 = now;
+                if let Some(j) = pos {
+                    let item = self.v.remove(i);
+                    self.v.insert(j, item);
+                    observer.event(Event::Move { from: i + 1, to: j + 1 });
+                }
+                i + 1
+            }
+            None => {
+                self.v.push((t, now, None));
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+// BIT (Reingold, Westbrook and Sleator): every element has a bit, random when
+// it joins the list, that flips on each access; an element moves to the front
+// when its bit turns to 1
+pub struct BITList<T> {
+    v: Vec<(T, bool)>,
+    coins: Coins,
+}
+
+impl<T> BITList<T> {
+    pub fn new() -> Self {
+        Self::with_coins(Coins::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_coins(Coins::seeded(seed))
+    }
+
+    pub fn with_coins(coins: Coins) -> Self {
+        Self{v: Vec::new(), coins}
+    }
+}
+
+impl<T> Default for BITList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Randomized for BITList<T> {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+impl_list!(@algorithm BITList, |list| distinct(list.v.iter().map(|(t, _)| t)));
+
+impl<T> Dynlist<T> for BITList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.v.iter().position(|v| v.0 == t) {
+            Some(i) => {
+                observer.event(Event::Hit { item: i + 1 });
+                self.v[i].1 = !self.v[i].1;
+                if self.v[i].1 && i > 0 {
+                    let item = self.v.remove(i);
+                    self.v.insert(0, item);
+                    observer.event(Event::Move { from: i + 1, to: 1 });
+                }
+                i + 1
+            }
+            None => {
+                let bit = self.coins.flip(0.5);
+                self.v.push((t, bit));
+                observer.event(Event::Miss { item: self.v.len() });
+                self.v.len()
+            }
+        }
+    }
+}
+
+// how often COMB runs BIT rather than TIMESTAMP
+pub const COMB_BIT: f64 = 0.8;
+
+// COMB (Albers, von Stengel and Werchner): BIT with probability 4/5 and
+// TIMESTAMP otherwise, decided anew for every run from BIT's coins
+pub struct COMBList<T> {
+    bit: BITList<T>,
+    ts: TSList<T>,
+    uses_bit: bool,
+}
+
+impl<T> COMBList<T> {
+    pub fn new() -> Self {
+        Self::with_coins(Coins::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_coins(Coins::seeded(seed))
+    }
+
+    pub fn with_coins(coins: Coins) -> Self {
+        let mut bit = BITList::with_coins(coins);
+        let uses_bit = bit.coins.flip(COMB_BIT);
+        Self{bit, ts: TSList::new(), uses_bit}
+    }
+}
+
+impl<T> Default for COMBList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Randomized for COMBList<T> {
+    fn coins(&self) -> &Coins {
+        &self.bit.coins
+    }
+}
+
+impl<T: Eq> OnlineAlgorithm for COMBList<T> {
+    type Request = T;
+    type Cost = usize;
+    type Error = Infallible;
+
+    fn serve_observed(&mut self, t: T, observer: &mut dyn Observer) -> Result<usize, Infallible> {
+        Ok(self.access_observed(t, observer))
+    }
+
+    fn reset(&mut self) {
+        self.bit.reset();
+        self.ts.reset();
+        self.uses_bit = self.bit.coins.flip(COMB_BIT);
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        // the list not in use stays empty
+        let (used, unused) = if self.uses_bit { (self.bit.v.len(), self.ts.v.len()) } else { (self.ts.v.len(), self.bit.v.len()) };
+        if unused > 0 {
+            return Err(format!("the unused list holds {} elements next to {}", unused, used));
+        }
+        if self.uses_bit { self.bit.check_invariants() } else { self.ts.check_invariants() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for COMBList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uses_bit { self.bit.fmt(f) } else { self.ts.fmt(f) }
+    }
+}
+
+impl<T> Dynlist<T> for COMBList<T>
+where T: Eq
+{
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        if self.uses_bit {
+            self.bit.access_observed(t, observer)
+        } else {
+            self.ts.access_observed(t, observer)
+        }
+    }
+}
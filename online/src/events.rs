@@ -0,0 +1,118 @@
+use std::io::{self, Write};
+
+// a decision an algorithm made while serving a request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    // the requested page is cached, or the requested list element is at 1-based position `item`
+    Hit { item: usize },
+    // the requested page is not cached, or the requested element was appended at position `item`
+    Miss { item: usize },
+    Evict { page: usize },
+    // a list element moved between two 1-based positions
+    Move { from: usize, to: usize },
+    // a marking or flushing cache starts a new phase
+    NewPhase,
+    Migrate { from: usize, to: usize, cost: usize },
+    Replicate { node: usize },
+    Drop { node: usize },
+    OpenBin { bin: usize },
+    Pack { bin: usize, size: f64 },
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Hit { .. } => "hit",
+            Event::Miss { .. } => "miss",
+            Event::Evict { .. } => "evict",
+            Event::Move { .. } => "move",
+            Event::NewPhase => "new_phase",
+            Event::Migrate { .. } => "migrate",
+            Event::Replicate { .. } => "replicate",
+            Event::Drop { .. } => "drop",
+            Event::OpenBin { .. } => "open_bin",
+            Event::Pack { .. } => "pack",
+        }
+    }
+
+    // the event as a JSON object, e.g. {"event":"evict","page":3}
+    pub fn to_json(&self) -> String {
+        let fields = self.json_fields();
+        let separator = if fields.is_empty() { "" } else { "," };
+        format!(r#"{{"event":"{}"{}{}}}"#, self.name(), separator, fields)
+    }
+
+    // the event's fields as a JSON object body, without the braces
+    fn json_fields(&self) -> String {
+        match *self {
+            Event::Hit { item } | Event::Miss { item } => format!(r#""item":{}"#, item),
+            Event::Evict { page } => format!(r#""page":{}"#, page),
+            Event::Move { from, to } => format!(r#""from":{},"to":{}"#, from, to),
+            Event::NewPhase => String::new(),
+            Event::Migrate { from, to, cost } => format!(r#""from":{},"to":{},"cost":{}"#, from, to, cost),
+            Event::Replicate { node } | Event::Drop { node } => format!(r#""node":{}"#, node),
+            Event::OpenBin { bin } => format!(r#""bin":{}"#, bin),
+            Event::Pack { bin, size } => format!(r#""bin":{},"size":{}"#, bin, size),
+        }
+    }
+}
+
+pub trait Observer {
+    fn event(&mut self, event: Event);
+}
+
+// ignores every event
+impl Observer for () {
+    fn event(&mut self, _: Event) {}
+}
+
+// collects events in memory
+impl Observer for Vec<Event> {
+    fn event(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+// writes one JSON object per event, tagged with the index of the request that
+// caused it; the first write error is kept and returned by `finish`
+pub struct JsonlSink<W: Write> {
+    writer: W,
+    request: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonlSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            request: 0,
+            error: None,
+        }
+    }
+
+    // called between requests so events are attributed to the right one
+    pub fn next_request(&mut self) {
+        self.request += 1;
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Observer for JsonlSink<W> {
+    fn event(&mut self, event: Event) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let line = format!(r#"{{"request":{},{}"#, self.request, &event.to_json()[1..]);
+        if let Err(error) = writeln!(self.writer, "{}", line) {
+            self.error = Some(error);
+        }
+    }
+}
@@ -0,0 +1,657 @@
+use crate::algorithm::OnlineAlgorithm;
+use crate::dynlist::{Dynlist, COMB_BIT};
+use crate::events::{Event, Observer};
+use crate::tape::{Coins, Randomized};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::Hash;
+
+// the same lists as in dynlist, with the same costs, but each access takes
+// O(log n) expected time instead of O(n): a hash map finds an element's node
+// and a sequence tree turns the node into a position and moves it
+
+const NIL: usize = usize::MAX;
+
+struct Node {
+    left: usize,
+    right: usize,
+    parent: usize,
+    priority: u64,
+    size: usize,
+    // what FC orders by, and the smallest key in the subtree
+    key: usize,
+    min: usize,
+}
+
+// an implicit treap over node ids: positions are given by subtree sizes, and
+// the parent links find the position of a node without searching for it
+struct Sequence {
+    nodes: Vec<Node>,
+    root: usize,
+    state: u64,
+}
+
+impl Sequence {
+    fn new() -> Self {
+        Self { nodes: Vec::new(), root: NIL, state: 0 }
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL { 0 } else { self.nodes[t].size }
+    }
+
+    fn min(&self, t: usize) -> usize {
+        if t == NIL { usize::MAX } else { self.nodes[t].min }
+    }
+
+    // splitmix64, so runs are reproducible without a generator to seed
+    fn priority(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn update(&mut self, t: usize) {
+        let (left, right) = (self.nodes[t].left, self.nodes[t].right);
+        self.nodes[t].size = 1 + self.size(left) + self.size(right);
+        self.nodes[t].min = self.nodes[t].key.min(self.min(left)).min(self.min(right));
+    }
+
+    fn set_left(&mut self, t: usize, child: usize) {
+        self.nodes[t].left = child;
+        if child != NIL {
+            self.nodes[child].parent = t;
+        }
+    }
+
+    fn set_right(&mut self, t: usize, child: usize) {
+        self.nodes[t].right = child;
+        if child != NIL {
+            self.nodes[child].parent = t;
+        }
+    }
+
+    // the first k elements of t, and the rest
+    fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let left = self.nodes[t].left;
+        if k <= self.size(left) {
+            let (a, b) = self.split(left, k);
+            self.set_left(t, b);
+            self.update(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.nodes[t].right, k - self.size(left) - 1);
+            self.set_right(t, a);
+            self.update(t);
+            (t, b)
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.set_right(a, right);
+            self.update(a);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.set_left(b, left);
+            self.update(b);
+            b
+        }
+    }
+
+    fn set_root(&mut self, t: usize) {
+        self.root = t;
+        if t != NIL {
+            self.nodes[t].parent = NIL;
+        }
+    }
+
+    // appends a new node and returns its id
+    fn push(&mut self, key: usize) -> usize {
+        let id = self.nodes.len();
+        let priority = self.priority();
+        self.nodes.push(Node { left: NIL, right: NIL, parent: NIL, priority, size: 1, key, min: key });
+        let root = self.merge(self.root, id);
+        self.set_root(root);
+        id
+    }
+
+    // 0-based position of node t
+    fn position(&self, mut t: usize) -> usize {
+        let mut position = self.size(self.nodes[t].left);
+        while self.nodes[t].parent != NIL {
+            let parent = self.nodes[t].parent;
+            if self.nodes[parent].right == t {
+                position += self.size(self.nodes[parent].left) + 1;
+            }
+            t = parent;
+        }
+        position
+    }
+
+    fn at(&self, mut position: usize) -> usize {
+        let mut t = self.root;
+        loop {
+            let left = self.size(self.nodes[t].left);
+            if position < left {
+                t = self.nodes[t].left;
+            } else if position == left {
+                return t;
+            } else {
+                position -= left + 1;
+                t = self.nodes[t].right;
+            }
+        }
+    }
+
+    fn remove(&mut self, position: usize) -> usize {
+        let (a, b) = self.split(self.root, position);
+        let (t, c) = self.split(b, 1);
+        let root = self.merge(a, c);
+        self.set_root(root);
+        self.nodes[t].parent = NIL;
+        t
+    }
+
+    fn insert(&mut self, position: usize, t: usize) {
+        let (a, b) = self.split(self.root, position);
+        let left = self.merge(a, t);
+        let root = self.merge(left, b);
+        self.set_root(root);
+    }
+
+    // position of the first node whose key is below `key`
+    fn first_below(&self, key: usize) -> Option<usize> {
+        if self.min(self.root) >= key {
+            return None;
+        }
+        let (mut t, mut offset) = (self.root, 0);
+        loop {
+            let left = self.nodes[t].left;
+            if self.min(left) < key {
+                t = left;
+            } else if self.nodes[t].key < key {
+                return Some(offset + self.size(left));
+            } else {
+                offset += self.size(left) + 1;
+                t = self.nodes[t].right;
+            }
+        }
+    }
+
+    fn order(&self) -> Vec<usize> {
+        let (mut order, mut stack, mut t) = (Vec::with_capacity(self.len()), Vec::new(), self.root);
+        while t != NIL || !stack.is_empty() {
+            while t != NIL {
+                stack.push(t);
+                t = self.nodes[t].left;
+            }
+            let top = stack.pop().unwrap();
+            order.push(top);
+            t = self.nodes[top].right;
+        }
+        order
+    }
+
+    // links, sizes, minima and priorities agree, and every node is in the tree
+    fn check(&self) -> Result<(), String> {
+        if self.root != NIL && self.nodes[self.root].parent != NIL {
+            return Err(format!("root {} has a parent", self.root));
+        }
+        for (t, node) in self.nodes.iter().enumerate() {
+            for child in [node.left, node.right].into_iter().filter(|&c| c != NIL) {
+                if self.nodes[child].parent != t {
+                    return Err(format!("node {} is a child of {} but not linked back", child, t));
+                }
+                if self.nodes[child].priority > node.priority {
+                    return Err(format!("node {} has a higher priority than its parent {}", child, t));
+                }
+            }
+            if node.size != 1 + self.size(node.left) + self.size(node.right) {
+                return Err(format!("node {} has size {}, expected {}", t, node.size, 1 + self.size(node.left) + self.size(node.right)));
+            }
+            if node.min != node.key.min(self.min(node.left)).min(self.min(node.right)) {
+                return Err(format!("node {} has a stale minimum", t));
+            }
+        }
+        if self.len() != self.nodes.len() {
+            return Err(format!("{} of {} nodes are in the tree", self.len(), self.nodes.len()));
+        }
+        Ok(())
+    }
+}
+
+// `$check` validates the list beyond the items and the tree being consistent,
+// `$entry` shows a node the way the plain list shows its element; any further
+// fields are listed with their initial values
+macro_rules! impl_indexed {
+    ($name: ident, $check: expr, |$list: ident, $node: ident| $entry: expr $(, $field: ident: $init: expr)*) => {
+        impl<T> $name<T> {
+            pub fn new() -> Self {
+                Self { items: Vec::new(), index: HashMap::new(), sequence: Sequence::new() $(, $field: $init)* }
+            }
+        }
+
+        impl<T> Default for $name<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl_indexed!(@algorithm $name, $check, |$list, $node| $entry $(, $field: $init)*);
+    };
+    // without constructors, for lists that keep their coins across resets
+    (@algorithm $name: ident, $check: expr, |$list: ident, $node: ident| $entry: expr $(, $field: ident: $init: expr)*) => {
+        impl<T: Hash + Eq + Clone> $name<T> {
+            // the node of t, or Err with a new one appended with the given key
+            fn node(&mut self, t: T, key: usize) -> Result<usize, usize> {
+                match self.index.get(&t) {
+                    Some(&node) => Ok(node),
+                    None => {
+                        let node = self.sequence.push(key);
+                        self.items.push(t.clone());
+                        self.index.insert(t, node);
+                        Err(node)
+                    }
+                }
+            }
+        }
+
+        impl<T: Hash + Eq + Clone> OnlineAlgorithm for $name<T> {
+            type Request = T;
+            type Cost = usize;
+            type Error = Infallible;
+
+            fn serve_observed(&mut self, t: T, observer: &mut dyn Observer) -> Result<usize, Infallible> {
+                Ok(self.access_observed(t, observer))
+            }
+
+            fn reset(&mut self) {
+                self.items.clear();
+                self.index.clear();
+                self.sequence.clear();
+                $(self.$field = $init;)*
+            }
+
+            fn check_invariants(&self) -> Result<(), String> {
+                self.sequence.check()?;
+                if self.items.len() != self.sequence.nodes.len() || self.index.len() != self.items.len() {
+                    return Err(format!(
+                        "{} items, {} indexed and {} nodes",
+                        self.items.len(), self.index.len(), self.sequence.nodes.len()
+                    ));
+                }
+                for (node, item) in self.items.iter().enumerate() {
+                    if self.index.get(item) != Some(&node) {
+                        return Err(format!("item of node {} is not indexed to it", node));
+                    }
+                }
+                let check: fn(&Self) -> Result<(), String> = $check;
+                check(self)
+            }
+        }
+
+        impl<T: fmt::Debug> fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $list = self;
+                let entries = $list.sequence.order().into_iter().map(|$node| $entry).collect::<Vec<_>>();
+                write!(f, "{:?}", entries)
+            }
+        }
+    };
+}
+
+// items are indexed by node id
+pub struct IndexedSimpleList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+}
+
+impl_indexed!(IndexedSimpleList, |_| Ok(()), |list, node| &list.items[node]);
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedSimpleList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.node(t, 0) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                i + 1
+            }
+            Err(_) => {
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+pub struct IndexedMTFList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+}
+
+impl_indexed!(IndexedMTFList, |_| Ok(()), |list, node| &list.items[node]);
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedMTFList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.node(t, 0) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                if i > 0 {
+                    self.sequence.remove(i);
+                    self.sequence.insert(0, node);
+                    observer.event(Event::Move { from: i + 1, to: 1 });
+                }
+                i + 1
+            }
+            Err(_) => {
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+pub struct IndexedTPList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+}
+
+impl_indexed!(IndexedTPList, |_| Ok(()), |list, node| &list.items[node]);
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedTPList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.node(t, 0) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                if i > 0 {
+                    // swapping the items of two neighbouring nodes leaves the tree alone
+                    let previous = self.sequence.at(i - 1);
+                    self.items.swap(node, previous);
+                    *self.index.get_mut(&self.items[node]).unwrap() = node;
+                    *self.index.get_mut(&self.items[previous]).unwrap() = previous;
+                    observer.event(Event::Move { from: i + 1, to: i });
+                }
+                i + 1
+            }
+            Err(_) => {
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+// node keys are access counts, in decreasing order along the list
+pub struct IndexedFCList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+}
+
+impl_indexed!(IndexedFCList, |list| {
+    let order = list.sequence.order();
+    if let Some(i) = order.iter().position(|&node| list.sequence.nodes[node].key == 0) {
+        return Err(format!("element at position {} was never accessed", i));
+    }
+    let count = |i: usize| list.sequence.nodes[order[i]].key;
+    match (1..order.len()).find(|&i| count(i - 1) < count(i)) {
+        Some(i) => Err(format!("counts increase from position {} ({}) to {} ({})", i - 1, count(i - 1), i, count(i))),
+        None => Ok(()),
+    }
+}, |list, node| (&list.items[node], list.sequence.nodes[node].key));
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedFCList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.node(t, 1) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                // out of the list, the node goes in front of the first element
+                // with a lower count than its new one, which is never behind i
+                self.sequence.remove(i);
+                let count = self.sequence.nodes[node].key + 1;
+                self.sequence.nodes[node].key = count;
+                self.sequence.nodes[node].min = count;
+                let j = self.sequence.first_below(count).unwrap_or(i);
+                self.sequence.insert(j, node);
+                if j < i {
+                    observer.event(Event::Move { from: i + 1, to: j + 1 });
+                }
+                i + 1
+            }
+            Err(_) => {
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+// TIMESTAMP, with node keys one more than the time of the request before an
+// element's last one, or 0 if there was none
+pub struct IndexedTSList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+    // time of each node's last request
+    last: Vec<usize>,
+    time: usize,
+}
+
+impl_indexed!(IndexedTSList, |list| {
+    if list.last.len() != list.items.len() {
+        return Err(format!("{} request times for {} items", list.last.len(), list.items.len()));
+    }
+    for (node, &last) in list.last.iter().enumerate() {
+        let key = list.sequence.nodes[node].key;
+        if last >= list.time || key > last {
+            return Err(format!("node {} has request times {} and {} at time {}", node, key, last, list.time));
+        }
+    }
+    Ok(())
+}, |list, node| {
+    let before = list.sequence.nodes[node].key.checked_sub(1);
+    (&list.items[node], list.last[node], before)
+}, last: Vec::new(), time: 0);
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedTSList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        let now = self.time;
+        self.time += 1;
+        match self.node(t, 0) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                // the first element whose request before its last one came
+                // before the last request here; the element itself is one
+                let last = self.last[node];
+                let j = self.sequence.first_below(last + 1).unwrap_or(i);
+                self.sequence.remove(i);
+                self.sequence.nodes[node].key = last + 1;
+                self.sequence.nodes[node].min = last + 1;
+                self.last[node] = now;
+                self.sequence.insert(j, node);
+                if j < i {
+                    observer.event(Event::Move { from: i + 1, to: j + 1 });
+                }
+                i + 1
+            }
+            Err(_) => {
+                self.last.push(now);
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+// BIT, with the bits indexed by node id
+pub struct IndexedBITList<T> {
+    items: Vec<T>,
+    index: HashMap<T, usize>,
+    sequence: Sequence,
+    bits: Vec<bool>,
+    coins: Coins,
+}
+
+impl<T> IndexedBITList<T> {
+    pub fn new() -> Self {
+        Self::with_coins(Coins::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_coins(Coins::seeded(seed))
+    }
+
+    pub fn with_coins(coins: Coins) -> Self {
+        Self { items: Vec::new(), index: HashMap::new(), sequence: Sequence::new(), bits: Vec::new(), coins }
+    }
+}
+
+impl<T> Default for IndexedBITList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Randomized for IndexedBITList<T> {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+impl_indexed!(@algorithm IndexedBITList, |list| {
+    if list.bits.len() != list.items.len() {
+        return Err(format!("{} bits for {} items", list.bits.len(), list.items.len()));
+    }
+    Ok(())
+}, |list, node| (&list.items[node], list.bits[node]), bits: Vec::new());
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedBITList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        match self.node(t, 0) {
+            Ok(node) => {
+                let i = self.sequence.position(node);
+                observer.event(Event::Hit { item: i + 1 });
+                self.bits[node] = !self.bits[node];
+                if self.bits[node] && i > 0 {
+                    self.sequence.remove(i);
+                    self.sequence.insert(0, node);
+                    observer.event(Event::Move { from: i + 1, to: 1 });
+                }
+                i + 1
+            }
+            Err(_) => {
+                let bit = self.coins.flip(0.5);
+                self.bits.push(bit);
+                observer.event(Event::Miss { item: self.sequence.len() });
+                self.sequence.len()
+            }
+        }
+    }
+}
+
+// COMB, drawing its choice from the coins of its BIT list like the plain one
+pub struct IndexedCOMBList<T> {
+    bit: IndexedBITList<T>,
+    ts: IndexedTSList<T>,
+    uses_bit: bool,
+}
+
+impl<T> IndexedCOMBList<T> {
+    pub fn new() -> Self {
+        Self::with_coins(Coins::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_coins(Coins::seeded(seed))
+    }
+
+    pub fn with_coins(coins: Coins) -> Self {
+        let mut bit = IndexedBITList::with_coins(coins);
+        let uses_bit = bit.coins.flip(COMB_BIT);
+        Self { bit, ts: IndexedTSList::new(), uses_bit }
+    }
+}
+
+impl<T> Default for IndexedCOMBList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Randomized for IndexedCOMBList<T> {
+    fn coins(&self) -> &Coins {
+        &self.bit.coins
+    }
+}
+
+impl<T: Hash + Eq + Clone> OnlineAlgorithm for IndexedCOMBList<T> {
+    type Request = T;
+    type Cost = usize;
+    type Error = Infallible;
+
+    fn serve_observed(&mut self, t: T, observer: &mut dyn Observer) -> Result<usize, Infallible> {
+        Ok(self.access_observed(t, observer))
+    }
+
+    fn reset(&mut self) {
+        self.bit.reset();
+        self.ts.reset();
+        self.uses_bit = self.bit.coins.flip(COMB_BIT);
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        // the list not in use stays empty
+        let (used, unused) = if self.uses_bit { (self.bit.items.len(), self.ts.items.len()) } else { (self.ts.items.len(), self.bit.items.len()) };
+        if unused > 0 {
+            return Err(format!("the unused list holds {} elements next to {}", unused, used));
+        }
+        if self.uses_bit { self.bit.check_invariants() } else { self.ts.check_invariants() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for IndexedCOMBList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uses_bit { self.bit.fmt(f) } else { self.ts.fmt(f) }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Dynlist<T> for IndexedCOMBList<T> {
+    fn access_observed(&mut self, t: T, observer: &mut dyn Observer) -> usize {
+        if self.uses_bit {
+            self.bit.access_observed(t, observer)
+        } else {
+            self.ts.access_observed(t, observer)
+        }
+    }
+}
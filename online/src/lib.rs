@@ -0,0 +1,25 @@
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod algorithm;
+pub mod events;
+
+#[cfg(feature = "list-update")]
+pub mod dynlist;
+
+#[cfg(feature = "list-update")]
+pub mod indexed;
+
+#[cfg(feature = "paging")]
+pub mod paging;
+
+#[cfg(feature = "bin-packing")]
+pub mod bin_packing;
+
+#[cfg(feature = "migration")]
+pub mod migration;
+
+#[cfg(feature = "allocation")]
+pub mod allocation;
+
+#[cfg(any(feature = "list-update", feature = "paging", feature = "bin-packing", feature = "migration"))]
+pub mod tape;
@@ -0,0 +1,189 @@
+use crate::algorithm::OnlineAlgorithm;
+use crate::events::{Event, Observer};
+use crate::tape::{Coins, Randomized};
+use metricgraph::*;
+use std::fmt;
+
+
+pub trait PageMigration {
+    fn read_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr>;
+
+    fn read(&mut self, source: usize) -> Result<usize, IndexErr> {
+        self.read_observed(source, &mut ())
+    }
+}
+
+fn on_graph(graph: &dyn MetricGraph, page: usize) -> Result<(), String> {
+    if page < graph.size() { Ok(()) } else { Err(format!("page at node {} outside the graph of {} nodes", page, graph.size())) }
+}
+
+pub struct MoveToMin {
+    graph: Box<dyn MetricGraph>,
+    start: usize,
+    page: usize,
+    d: usize,
+    buffer: Vec<usize>,
+}
+
+impl MoveToMin {
+    pub fn new(graph: Box<dyn MetricGraph>, page: usize, d: usize) -> Self {
+        Self {
+            graph,
+            start: page,
+            page,
+            d,
+            buffer: Vec::with_capacity(d),
+        }
+    }
+    
+    fn migrate(&mut self, target: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let cost = self.d * self.graph.distance(self.page, target)?;
+        if target != self.page {
+            observer.event(Event::Migrate { from: self.page, to: target, cost });
+        }
+        self.page = target;
+        Ok(cost)
+    }
+
+    fn find_min(&self) -> usize {
+        (0..self.graph.size())
+            .map(|candidate| {
+                (candidate, self.buffer
+                    .iter()
+                    .map(|&src| self.graph.distance(candidate, src).unwrap())
+                    .sum::<usize>()
+                )
+            })
+            .min_by_key(|&(_, total_cost)| total_cost)
+            .map(|(best_node, _)| best_node)
+            .unwrap()
+    }
+}
+
+impl fmt::Display for MoveToMin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "page at node {}, buffered requests {:?} ({} of {})", self.page, self.buffer, self.buffer.len(), self.d)
+    }
+}
+
+impl PageMigration for MoveToMin {
+    fn read_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let mut cost = self.graph.distance(self.page, source)?;
+        
+        self.buffer.push(source);
+
+        if self.buffer.len() == self.d {
+            let best = self.find_min();
+            cost += self.migrate(best, observer)?;
+            self.buffer.clear();
+        }
+
+        Ok(cost)
+    }
+}
+
+impl OnlineAlgorithm for MoveToMin {
+    type Request = usize;
+    type Cost = usize;
+    type Error = IndexErr;
+
+    fn serve_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        self.read_observed(source, observer)
+    }
+
+    fn reset(&mut self) {
+        self.page = self.start;
+        self.buffer.clear();
+    }
+
+    // the buffer is emptied whenever it reaches d requests
+    fn check_invariants(&self) -> Result<(), String> {
+        on_graph(self.graph.as_ref(), self.page)?;
+        if self.buffer.len() >= self.d.max(1) {
+            return Err(format!("{} requests buffered with d = {}", self.buffer.len(), self.d));
+        }
+        match self.buffer.iter().find(|&&source| source >= self.graph.size()) {
+            Some(source) => Err(format!("buffered request from node {} outside the graph", source)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct CoinFlip {
+    graph: Box<dyn MetricGraph>,
+    start: usize,
+    page: usize,
+    d: usize,
+    coins: Coins,
+}
+
+impl CoinFlip {
+    pub fn new(graph: Box<dyn MetricGraph>, page: usize, d: usize) -> Self {
+        Self::with_coins(graph, page, d, Coins::from_entropy())
+    }
+
+    pub fn with_seed(graph: Box<dyn MetricGraph>, page: usize, d: usize, seed: u64) -> Self {
+        Self::with_coins(graph, page, d, Coins::seeded(seed))
+    }
+
+    pub fn with_coins(graph: Box<dyn MetricGraph>, page: usize, d: usize, coins: Coins) -> Self {
+        Self {
+            graph,
+            start: page,
+            page,
+            d,
+            coins,
+        }
+    }
+    
+    fn migrate(&mut self, target: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let cost = self.d * self.graph.distance(self.page, target)?;
+        if target != self.page {
+            observer.event(Event::Migrate { from: self.page, to: target, cost });
+        }
+        self.page = target;
+        Ok(cost)
+    }
+}
+
+impl Randomized for CoinFlip {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+impl fmt::Display for CoinFlip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "page at node {}", self.page)
+    }
+}
+
+impl PageMigration for CoinFlip {
+    fn read_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        let mut cost = self.graph.distance(self.page, source)?;
+
+        if self.coins.flip(1.0 / (2.0 * self.d as f64)) {
+            cost += self.migrate(source, observer)?;
+        }
+
+        Ok(cost)
+    }
+}
+
+impl OnlineAlgorithm for CoinFlip {
+    type Request = usize;
+    type Cost = usize;
+    type Error = IndexErr;
+
+    fn serve_observed(&mut self, source: usize, observer: &mut dyn Observer) -> Result<usize, IndexErr> {
+        self.read_observed(source, observer)
+    }
+
+    fn reset(&mut self) {
+        self.page = self.start;
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        on_graph(self.graph.as_ref(), self.page)
+    }
+}
@@ -0,0 +1,453 @@
+use crate::algorithm::{distinct, OnlineAlgorithm};
+use crate::events::{Event, Observer};
+use crate::tape::{Coins, Randomized};
+use std::convert::Infallible;
+use std::fmt;
+
+pub trait Cacher<T> {
+    fn access_observed(&mut self, page: T, observer: &mut dyn Observer) -> usize;
+
+    fn access(&mut self, page: T) -> usize {
+        self.access_observed(page, &mut ())
+    }
+}
+
+// serving a page never fails; `$reset` empties the cache and `$check`
+// validates it
+macro_rules! impl_online {
+    ($name: ident, $reset: expr, $check: expr) => {
+        impl OnlineAlgorithm for $name {
+            type Request = usize;
+            type Cost = usize;
+            type Error = Infallible;
+
+            fn serve_observed(&mut self, page: usize, observer: &mut dyn Observer) -> Result<usize, Infallible> {
+                Ok(self.access_observed(page, observer))
+            }
+
+            fn reset(&mut self) {
+                let reset: fn(&mut Self) = $reset;
+                reset(self)
+            }
+
+            fn check_invariants(&self) -> Result<(), String> {
+                let check: fn(&Self) -> Result<(), String> = $check;
+                check(self)
+            }
+        }
+    }
+}
+
+// no page is cached twice; empty slots do not count
+fn distinct_pages<'a>(pages: impl Iterator<Item = &'a usize>) -> Result<(), String> {
+    distinct(pages.filter(|&&p| p != 0)).map_err(|e| format!("cached pages: {}", e))
+}
+
+// the first `used` slots hold pages and the rest are empty
+fn filled(cache: &[usize], used: usize) -> Result<(), String> {
+    if used > cache.len() {
+        return Err(format!("{} slots used out of {}", used, cache.len()));
+    }
+    if let Some(i) = cache[..used].iter().position(|&p| p == 0) {
+        return Err(format!("slot {} is empty but counted as used", i));
+    }
+    match cache[used..].iter().position(|&p| p != 0) {
+        Some(i) => Err(format!("slot {} holds a page but is counted as free", used + i)),
+        None => Ok(()),
+    }
+}
+
+fn sized(cache_len: usize, size: usize) -> Result<(), String> {
+    if cache_len == size { Ok(()) } else { Err(format!("{} slots in a cache of size {}", cache_len, size)) }
+}
+
+// 0 marks an empty slot
+fn show(page: usize) -> String {
+    if page == 0 { "_".to_string() } else { page.to_string() }
+}
+
+fn show_all<'a>(pages: impl Iterator<Item = &'a usize>) -> String {
+    pages.map(|&p| show(p)).collect::<Vec<_>>().join(", ")
+}
+
+// 0 marks an empty slot, so it is never reported as evicted
+fn evict(observer: &mut dyn Observer, page: usize) {
+    if page != 0 {
+        observer.event(Event::Evict { page });
+    }
+}
+
+#[derive(Debug)]
+pub struct FIFO {
+    cache: Vec<usize>,
+    size: usize,
+    index: usize,
+}
+
+impl FIFO {
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: vec![0; size],
+            size,
+            index: 0,
+        }
+    }
+}
+
+impl fmt::Display for FIFO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}], next slot {}", show_all(self.cache.iter()), self.index)
+    }
+}
+
+impl Cacher<usize> for FIFO {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        match self.cache.iter().position(|&v| v == page) {
+            Some(_) => {
+                observer.event(Event::Hit { item: page });
+                0
+            }
+            None => {
+                observer.event(Event::Miss { item: page });
+                evict(observer, self.cache[self.index]);
+                self.cache[self.index] = page;
+                self.index = (self.index + 1) % self.size;
+                1
+            }
+        }
+    }
+}
+
+impl_online!(FIFO, |fifo| *fifo = FIFO::new(fifo.size), |fifo| {
+    sized(fifo.cache.len(), fifo.size)?;
+    if fifo.index >= fifo.size {
+        return Err(format!("next slot {} is out of range", fifo.index));
+    }
+    distinct_pages(fifo.cache.iter())
+});
+
+#[derive(Debug)]
+pub struct FWF {
+    cache: Vec<usize>,
+    size: usize,
+    index: usize,
+}
+
+impl FWF {
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: vec![0; size],
+            size,
+            index: 0,
+        }
+    }
+}
+
+impl fmt::Display for FWF {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}], {} of {} slots used", show_all(self.cache.iter()), self.index, self.size)
+    }
+}
+
+impl Cacher<usize> for FWF {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        match self.cache.iter().position(|&v| v == page) {
+            Some(_) => {
+                observer.event(Event::Hit { item: page });
+                0
+            }
+            None => {
+                observer.event(Event::Miss { item: page });
+                if self.index == self.size {
+                    observer.event(Event::NewPhase);
+                    for &old in &self.cache {
+                        evict(observer, old);
+                    }
+                    self.cache.fill(0);
+                    self.index = 0;
+                }
+                self.cache[self.index] = page;
+                self.index += 1;
+                1
+            }
+        }
+    }
+}
+
+impl_online!(FWF, |fwf| *fwf = FWF::new(fwf.size), |fwf| {
+    sized(fwf.cache.len(), fwf.size)?;
+    filled(&fwf.cache, fwf.index)?;
+    distinct_pages(fwf.cache.iter())
+});
+
+#[derive(Debug)]
+pub struct LRU {
+    cache: Vec<usize>,
+}
+
+impl LRU {
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: vec![0; size],
+        }
+    }
+}
+
+impl fmt::Display for LRU {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}], least recently used first", show_all(self.cache.iter()))
+    }
+}
+
+impl Cacher<usize> for LRU {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        match self.cache.iter().position(|&v| v == page) {
+            Some(i) => {
+                observer.event(Event::Hit { item: page });
+                self.cache.remove(i);
+                self.cache.push(page);
+                0
+            },
+            None => {
+                observer.event(Event::Miss { item: page });
+                evict(observer, self.cache.remove(0));
+                self.cache.push(page);
+                1
+            }
+        }
+    }
+}
+
+// empty slots are the least recently used, so they come first
+impl_online!(LRU, |lru| *lru = LRU::new(lru.cache.len()), |lru| {
+    let empty = lru.cache.iter().take_while(|&&p| p == 0).count();
+    if let Some(i) = lru.cache[empty..].iter().position(|&p| p == 0) {
+        return Err(format!("empty slot {} follows a cached page", empty + i));
+    }
+    distinct_pages(lru.cache.iter())
+});
+
+#[derive(Debug)]
+pub struct LFU {
+    cache: Vec<usize>,
+    count: Vec<usize>,
+}
+
+impl LFU {
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: vec![0; size],
+            count: vec![0; size + 1], // 0th index is dummy for 'empty'
+        }
+    }
+}
+
+impl fmt::Display for LFU {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pages = self.cache
+            .iter()
+            .map(|&p| if p == 0 { show(p) } else { format!("{} (x{})", p, self.count[p]) })
+            .collect::<Vec<_>>();
+        write!(f, "[{}]", pages.join(", "))
+    }
+}
+
+impl Cacher<usize> for LFU {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        if page >= self.count.len() {
+            self.count.resize(page + 1, 0);
+        }
+        self.count[page] += 1;
+
+        match self.cache.iter().position(|&v| v == page) {
+            Some(_) => {
+                observer.event(Event::Hit { item: page });
+                0
+            }
+            None => {
+                observer.event(Event::Miss { item: page });
+                let min = self.cache
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, v)| self.count[**v])
+                    .map(|(i, _)| i)
+                    .unwrap();
+                evict(observer, self.cache[min]);
+                self.cache[min] = page;
+                1
+            }
+        }
+    }
+}
+
+impl_online!(LFU, |lfu| *lfu = LFU::new(lfu.cache.len()), |lfu| {
+    if let Some(&p) = lfu.cache.iter().find(|&&p| p != 0 && lfu.count.get(p).is_none_or(|&c| c == 0)) {
+        return Err(format!("page {} is cached but was never accessed", p));
+    }
+    distinct_pages(lfu.cache.iter())
+});
+
+#[derive(Debug)]
+pub struct RAND {
+    cache: Vec<usize>,
+    size: usize,
+    taken: usize,
+    coins: Coins,
+}
+
+impl RAND {
+    pub fn new(size: usize) -> Self {
+        Self::with_coins(size, Coins::from_entropy())
+    }
+
+    pub fn with_seed(size: usize, seed: u64) -> Self {
+        Self::with_coins(size, Coins::seeded(seed))
+    }
+
+    pub fn with_coins(size: usize, coins: Coins) -> Self {
+        Self {
+            cache: vec![0; size],
+            size,
+            taken: 0,
+            coins,
+        }
+    }
+}
+
+impl Randomized for RAND {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+impl fmt::Display for RAND {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", show_all(self.cache.iter()))
+    }
+}
+    
+impl Cacher<usize> for RAND {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        match self.cache.iter().position(|&v| v == page) {
+            Some(_) => {
+                observer.event(Event::Hit { item: page });
+                0
+            }
+            None => {
+                observer.event(Event::Miss { item: page });
+                if self.taken == self.size {
+                    let i = self.coins.index(self.size);
+                    evict(observer, self.cache[i]);
+                    self.cache[i] = page;
+                } else {
+                    self.cache[self.taken] = page;
+                    self.taken += 1;
+                }
+                1
+            }
+        }
+    }
+}
+
+impl_online!(RAND, |rand| {
+    rand.cache.fill(0);
+    rand.taken = 0;
+}, |rand| {
+    sized(rand.cache.len(), rand.size)?;
+    filled(&rand.cache, rand.taken)?;
+    distinct_pages(rand.cache.iter())
+});
+
+#[derive(Debug)]
+pub struct RMA {
+    cache: Vec<(usize, bool)>,
+    size: usize,
+    marked: usize,
+    coins: Coins,
+}
+
+impl RMA {
+    pub fn new(size: usize) -> Self {
+        Self::with_coins(size, Coins::from_entropy())
+    }
+
+    pub fn with_seed(size: usize, seed: u64) -> Self {
+        Self::with_coins(size, Coins::seeded(seed))
+    }
+
+    pub fn with_coins(size: usize, coins: Coins) -> Self {
+        Self {
+            cache: vec![(0, false); size],
+            size,
+            marked: 0,
+            coins,
+        }
+    }
+}
+
+impl Randomized for RMA {
+    fn coins(&self) -> &Coins {
+        &self.coins
+    }
+}
+
+// marked pages carry a star
+impl fmt::Display for RMA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pages = self.cache
+            .iter()
+            .map(|&(p, marked)| if marked { format!("{}*", show(p)) } else { show(p) })
+            .collect::<Vec<_>>();
+        write!(f, "[{}], {} of {} marked", pages.join(", "), self.marked, self.size)
+    }
+}
+
+impl Cacher<usize> for RMA {
+    fn access_observed(&mut self, page: usize, observer: &mut dyn Observer) -> usize {
+        match self.cache.iter().position(|(v, _)| *v == page) {
+            Some(i) => {
+                observer.event(Event::Hit { item: page });
+                if !self.cache[i].1 {
+                    self.cache[i].1 = true;
+                    self.marked += 1;
+                }
+                0
+            }
+            None => {
+                observer.event(Event::Miss { item: page });
+                if self.marked == self.size {
+                    observer.event(Event::NewPhase);
+                    self.cache.iter_mut().for_each(|(_, marked)| *marked = false);
+                    self.marked = 0;
+                }
+                let rand = self.coins.index(self.size - self.marked);
+                let i = self.cache
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, marked))| !*marked)
+                    .nth(rand)
+                    .map(|(i, _)| i)
+                    .unwrap();
+                evict(observer, self.cache[i].0);
+                self.cache[i] = (page, true);
+                self.marked += 1;
+                1
+            }
+        }
+    }
+}
+
+impl_online!(RMA, |rma| {
+    rma.cache.fill((0, false));
+    rma.marked = 0;
+}, |rma| {
+    sized(rma.cache.len(), rma.size)?;
+    let flags = rma.cache.iter().filter(|(_, marked)| *marked).count();
+    if flags != rma.marked {
+        return Err(format!("{} pages carry a mark but {} are counted as marked", flags, rma.marked));
+    }
+    if let Some(i) = rma.cache.iter().position(|&(p, marked)| p == 0 && marked) {
+        return Err(format!("empty slot {} is marked", i));
+    }
+    distinct_pages(rma.cache.iter().map(|(p, _)| p))
+});
@@ -0,0 +1,178 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+// one random decision of an algorithm
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Choice {
+    // a uniform pick among `of` options
+    Index { chosen: usize, of: usize },
+    Flip(bool),
+}
+
+// "3/5" is option 3 of 0..5, "heads" and "tails" are coin flips
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Choice::Index { chosen, of } => write!(f, "{}/{}", chosen, of),
+            Choice::Flip(true) => write!(f, "heads"),
+            Choice::Flip(false) => write!(f, "tails"),
+        }
+    }
+}
+
+impl Choice {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "heads" => Some(Choice::Flip(true)),
+            "tails" => Some(Choice::Flip(false)),
+            _ => {
+                let (chosen, of) = text.split_once('/')?;
+                let (chosen, of) = (chosen.parse().ok()?, of.parse().ok()?);
+                (chosen < of).then_some(Choice::Index { chosen, of })
+            }
+        }
+    }
+}
+
+// the decisions of one run, in the order they were made
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Tape {
+    pub choices: Vec<Choice>,
+}
+
+impl Tape {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // one choice per line
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for choice in &self.choices {
+            writeln!(writer, "{}", choice)?;
+        }
+        writer.flush()
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut choices = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let choice = Choice::parse(line.trim()).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: invalid choice {}", i + 1, line))
+            })?;
+            choices.push(choice);
+        }
+        Ok(Self { choices })
+    }
+}
+
+// what a run asks the coins for, named only when a replay goes wrong
+#[derive(Copy, Clone)]
+enum Wanted {
+    Index(usize),
+    Flip,
+}
+
+impl fmt::Display for Wanted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Wanted::Index(n) => write!(f, "one of {} options", n),
+            Wanted::Flip => write!(f, "a coin flip"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Mode {
+    Draw,
+    Record(Tape),
+    Replay(Tape, usize),
+}
+
+// where a randomized algorithm takes its decisions from: a generator, a
+// generator whose every decision is logged to a tape, or a recorded tape
+#[derive(Debug)]
+pub struct Coins {
+    rng: StdRng,
+    mode: Mode,
+}
+
+impl Coins {
+    pub fn from_entropy() -> Self {
+        Self { rng: StdRng::from_entropy(), mode: Mode::Draw }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), mode: Mode::Draw }
+    }
+
+    // keeps drawing as before, logging every choice
+    pub fn recording(self) -> Self {
+        Self { mode: Mode::Record(Tape::new()), ..self }
+    }
+
+    // makes exactly the recorded choices; a run that asks for anything else
+    // than the tape holds panics, pointing at the first difference
+    pub fn replaying(tape: Tape) -> Self {
+        Self { rng: StdRng::seed_from_u64(0), mode: Mode::Replay(tape, 0) }
+    }
+
+    // the recorded or replayed tape
+    pub fn tape(&self) -> Option<&Tape> {
+        match &self.mode {
+            Mode::Draw => None,
+            Mode::Record(tape) | Mode::Replay(tape, _) => Some(tape),
+        }
+    }
+
+    fn decide(&mut self, draw: impl FnOnce(&mut StdRng) -> Choice, wanted: Wanted) -> Choice {
+        match &mut self.mode {
+            Mode::Draw => draw(&mut self.rng),
+            Mode::Record(tape) => {
+                let choice = draw(&mut self.rng);
+                tape.choices.push(choice);
+                choice
+            }
+            Mode::Replay(tape, position) => {
+                let Some(&choice) = tape.choices.get(*position) else {
+                    panic!("the tape ends after {} choices, but the run asks for {}", position, wanted);
+                };
+                *position += 1;
+                choice
+            }
+        }
+    }
+
+    // a uniform index in 0..n
+    pub fn index(&mut self, n: usize) -> usize {
+        match self.decide(|rng| Choice::Index { chosen: rng.gen_range(0..n), of: n }, Wanted::Index(n)) {
+            Choice::Index { chosen, of } if of == n => chosen,
+            choice => panic!("choice {} on the tape is {}, but the run asks for {}", self.position(), choice, Wanted::Index(n)),
+        }
+    }
+
+    // true with probability p
+    pub fn flip(&mut self, p: f64) -> bool {
+        match self.decide(|rng| Choice::Flip(rng.r#gen::<f64>() < p), Wanted::Flip) {
+            Choice::Flip(heads) => heads,
+            choice => panic!("choice {} on the tape is {}, but the run asks for {}", self.position(), choice, Wanted::Flip),
+        }
+    }
+
+    fn position(&self) -> usize {
+        match &self.mode {
+            Mode::Replay(_, position) => *position,
+            _ => 0,
+        }
+    }
+}
+
+// an algorithm whose decisions come from coins
+pub trait Randomized {
+    fn coins(&self) -> &Coins;
+}
@@ -0,0 +1,463 @@
+use online::algorithm::OnlineAlgorithm;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use std::collections::HashSet;
+use std::convert::Infallible;
+
+type Served<A> = Vec<Result<<A as OnlineAlgorithm>::Cost, <A as OnlineAlgorithm>::Error>>;
+
+// serves the requests one at a time, checking the invariants before the first,
+// after every one and again after a reset; returns the result of each request
+fn run<A: OnlineAlgorithm>(algorithm: &mut A, requests: &[A::Request]) -> Result<Served<A>, TestCaseError>
+where A::Request: Clone
+{
+    algorithm.check_invariants().map_err(|e| TestCaseError::fail(format!("initially: {}", e)))?;
+    let mut results = Vec::new();
+    for (i, request) in requests.iter().enumerate() {
+        results.push(algorithm.serve(request.clone()));
+        algorithm.check_invariants().map_err(|e| TestCaseError::fail(format!("after request {}: {}", i + 1, e)))?;
+    }
+    algorithm.reset();
+    algorithm.check_invariants().map_err(|e| TestCaseError::fail(format!("after reset: {}", e)))?;
+    Ok(results)
+}
+
+#[cfg(feature = "list-update")]
+mod list_update {
+    use super::*;
+    use online::dynlist::*;
+    use online::indexed::*;
+    use online::tape::{Choice, Coins, Randomized, Tape};
+
+    fn costs<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible>>(list: &mut A, requests: &[usize]) -> Result<Vec<usize>, TestCaseError> {
+        let costs = run(list, requests)?.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+        // an access costs the element's 1-based position, at most the number of
+        // distinct elements seen so far
+        for (i, &cost) in costs.iter().enumerate() {
+            let seen = requests[..=i].iter().collect::<HashSet<_>>().len();
+            prop_assert!((1..=seen).contains(&cost), "request {} cost {} with {} elements", i + 1, cost, seen);
+        }
+        Ok(costs)
+    }
+
+    fn check<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible>>(mut list: A, requests: &[usize]) -> Result<(), TestCaseError> {
+        let first = costs(&mut list, requests)?;
+        // after a reset the list behaves as a fresh one
+        let second = costs(&mut list, requests)?;
+        prop_assert_eq!(first, second);
+        Ok(())
+    }
+
+    // a randomized list keeps drawing after a reset, so only the costs are checked
+    fn check_randomized<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible>>(mut list: A, requests: &[usize]) -> Result<(), TestCaseError> {
+        costs(&mut list, requests)?;
+        costs(&mut list, requests)?;
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn simple(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(SimpleList::new(), &requests)?;
+        }
+
+        #[test]
+        fn mtf(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(MTFList::new(), &requests)?;
+        }
+
+        #[test]
+        fn tp(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(TPList::new(), &requests)?;
+        }
+
+        #[test]
+        fn fc(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(FCList::new(), &requests)?;
+        }
+
+        #[test]
+        fn ts(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(TSList::new(), &requests)?;
+        }
+
+        #[test]
+        fn indexed_ts(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(IndexedTSList::new(), &requests)?;
+            same(TSList::new(), IndexedTSList::new(), &requests)?;
+        }
+
+        #[test]
+        fn indexed_simple(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(IndexedSimpleList::new(), &requests)?;
+            same(SimpleList::new(), IndexedSimpleList::new(), &requests)?;
+        }
+
+        #[test]
+        fn indexed_mtf(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(IndexedMTFList::new(), &requests)?;
+            same(MTFList::new(), IndexedMTFList::new(), &requests)?;
+        }
+
+        #[test]
+        fn indexed_tp(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(IndexedTPList::new(), &requests)?;
+            same(TPList::new(), IndexedTPList::new(), &requests)?;
+        }
+
+        #[test]
+        fn indexed_fc(requests in prop::collection::vec(1..20usize, 0..200)) {
+            check(IndexedFCList::new(), &requests)?;
+            same(FCList::new(), IndexedFCList::new(), &requests)?;
+        }
+
+        #[test]
+        fn bit(seed: u64, requests in prop::collection::vec(1..20usize, 0..200)) {
+            check_randomized(BITList::with_seed(seed), &requests)?;
+        }
+
+        #[test]
+        fn comb(seed: u64, requests in prop::collection::vec(1..20usize, 0..200)) {
+            check_randomized(COMBList::with_seed(seed), &requests)?;
+        }
+
+        #[test]
+        fn indexed_bit(seed: u64, requests in prop::collection::vec(1..20usize, 0..200)) {
+            check_randomized(IndexedBITList::with_seed(seed), &requests)?;
+            same(BITList::with_seed(seed), IndexedBITList::with_seed(seed), &requests)?;
+        }
+
+        #[test]
+        fn indexed_comb(seed: u64, requests in prop::collection::vec(1..20usize, 0..200)) {
+            check_randomized(IndexedCOMBList::with_seed(seed), &requests)?;
+            same(COMBList::with_seed(seed), IndexedCOMBList::with_seed(seed), &requests)?;
+        }
+    }
+
+    // a request with its cost and the list after it
+    type Step<E> = (usize, usize, &'static [E]);
+
+    // TIMESTAMP worked out by hand, with each element's time of its last
+    // request and of the one before
+    const TIMESTAMP: &[Step<(usize, usize, Option<usize>)>] = &[
+        (1, 1, &[(1, 0, None)]),
+        (2, 2, &[(1, 0, None), (2, 1, None)]),
+        (3, 3, &[(1, 0, None), (2, 1, None), (3, 2, None)]),
+        // 1 was not requested since 3 was, so 3 goes in front of it
+        (3, 3, &[(3, 3, Some(2)), (1, 0, None), (2, 1, None)]),
+        // 3 was requested twice since 2 was, 1 not at all
+        (2, 3, &[(3, 3, Some(2)), (2, 4, Some(1)), (1, 0, None)]),
+        // both were requested twice since 1 was, so 1 stays last
+        (1, 3, &[(3, 3, Some(2)), (2, 4, Some(1)), (1, 5, Some(0))]),
+        (1, 3, &[(1, 6, Some(5)), (3, 3, Some(2)), (2, 4, Some(1))]),
+        (3, 2, &[(1, 6, Some(5)), (3, 7, Some(3)), (2, 4, Some(1))]),
+        (2, 3, &[(1, 6, Some(5)), (2, 8, Some(4)), (3, 7, Some(3))]),
+        (4, 4, &[(1, 6, Some(5)), (2, 8, Some(4)), (3, 7, Some(3)), (4, 9, None)]),
+        (4, 4, &[(4, 10, Some(9)), (1, 6, Some(5)), (2, 8, Some(4)), (3, 7, Some(3))]),
+    ];
+
+    fn follow_timestamp<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible> + std::fmt::Display>(mut list: A) {
+        for (i, &(request, cost, after)) in TIMESTAMP.iter().enumerate() {
+            assert_eq!(list.serve(request), Ok(cost), "request {}", i + 1);
+            assert_eq!(list.to_string(), format!("{:?}", after), "after request {}", i + 1);
+        }
+    }
+
+    #[test]
+    fn ts_by_hand() {
+        follow_timestamp(TSList::new());
+    }
+
+    #[test]
+    fn indexed_ts_by_hand() {
+        follow_timestamp(IndexedTSList::new());
+    }
+
+    // BIT with the initial bits 1, 0 and 1 for the elements 1, 2 and 3, with
+    // each element's bit
+    const BIT: &[Step<(usize, bool)>] = &[
+        (1, 1, &[(1, true)]),
+        (2, 2, &[(1, true), (2, false)]),
+        (3, 3, &[(1, true), (2, false), (3, true)]),
+        // a bit flipping to 0 leaves the element in place
+        (3, 3, &[(1, true), (2, false), (3, false)]),
+        (3, 3, &[(3, true), (1, true), (2, false)]),
+        (2, 3, &[(2, true), (3, true), (1, true)]),
+        (1, 3, &[(2, true), (3, true), (1, false)]),
+        (1, 3, &[(1, true), (2, true), (3, true)]),
+        (2, 2, &[(1, true), (2, false), (3, true)]),
+        (2, 2, &[(2, true), (1, true), (3, true)]),
+        (2, 1, &[(2, false), (1, true), (3, true)]),
+        (2, 1, &[(2, true), (1, true), (3, true)]),
+    ];
+
+    fn follow_bit<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible> + std::fmt::Display>(make: impl Fn(Coins) -> A) {
+        let tape = Tape { choices: vec![Choice::Flip(true), Choice::Flip(false), Choice::Flip(true)] };
+        let mut list = make(Coins::replaying(tape));
+        for (i, &(request, cost, after)) in BIT.iter().enumerate() {
+            assert_eq!(list.serve(request), Ok(cost), "request {}", i + 1);
+            assert_eq!(list.to_string(), format!("{:?}", after), "after request {}", i + 1);
+        }
+    }
+
+    #[test]
+    fn bit_by_hand() {
+        follow_bit(BITList::with_coins);
+    }
+
+    #[test]
+    fn indexed_bit_by_hand() {
+        follow_bit(IndexedBITList::with_coins);
+    }
+
+    // a seeded BIT moves an element to the front exactly when its bit turns to
+    // 1, with the initial bits taken from its recorded coin flips
+    fn bit_moves<A>(make: impl Fn(Coins) -> A, seed: u64, requests: &[usize]) -> Result<(), TestCaseError>
+    where A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible> + Randomized + std::fmt::Display
+    {
+        let mut list = make(Coins::seeded(seed).recording());
+        let mut expected: Vec<(usize, bool)> = Vec::new();
+        for (i, &request) in requests.iter().enumerate() {
+            let cost = list.serve(request).unwrap();
+            match expected.iter().position(|&(e, _)| e == request) {
+                Some(at) => {
+                    prop_assert_eq!(cost, at + 1, "request {}", i + 1);
+                    expected[at].1 = !expected[at].1;
+                    if expected[at].1 {
+                        let entry = expected.remove(at);
+                        expected.insert(0, entry);
+                    }
+                }
+                None => {
+                    let flips = list.coins().tape().unwrap().choices.clone();
+                    let Some(&Choice::Flip(bit)) = flips.get(expected.len()) else {
+                        return Err(TestCaseError::fail(format!("no coin flip for the new element of request {}", i + 1)));
+                    };
+                    expected.push((request, bit));
+                    prop_assert_eq!(cost, expected.len(), "request {}", i + 1);
+                }
+            }
+            prop_assert_eq!(list.to_string(), format!("{:?}", expected), "after request {}", i + 1);
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn bit_moves_on_flips_to_one(seed: u64, requests in prop::collection::vec(1..10usize, 0..100)) {
+            bit_moves(BITList::with_coins, seed, &requests)?;
+            bit_moves(IndexedBITList::with_coins, seed, &requests)?;
+        }
+    }
+
+    // the first flip of COMB's coins picks BIT, which should win COMB_BIT of the time
+    #[test]
+    fn comb_picks_bit_with_its_probability() {
+        let seeds = 10_000;
+        let picks = |make: &dyn Fn(Coins) -> Box<dyn Randomized>| {
+            (0..seeds).filter(|&seed| make(Coins::seeded(seed).recording()).coins().tape().unwrap().choices[0] == Choice::Flip(true)).count()
+        };
+        for bits in [picks(&|coins| Box::new(COMBList::<usize>::with_coins(coins))), picks(&|coins| Box::new(IndexedCOMBList::<usize>::with_coins(coins)))] {
+            let share = bits as f64 / seeds as f64;
+            // four standard deviations of the binomial share
+            let tolerance = 4.0 * (COMB_BIT * (1.0 - COMB_BIT) / seeds as f64).sqrt();
+            assert!((share - COMB_BIT).abs() < tolerance, "BIT in {} of {} runs", bits, seeds);
+        }
+    }
+
+    // an indexed list pays what its plain counterpart does and ends in the same order
+    fn same<A, B>(mut plain: A, mut indexed: B, requests: &[usize]) -> Result<(), TestCaseError>
+    where
+        A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible> + std::fmt::Display,
+        B: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible> + std::fmt::Display,
+    {
+        for (i, &request) in requests.iter().enumerate() {
+            prop_assert_eq!(plain.serve(request), indexed.serve(request), "request {}", i + 1);
+        }
+        prop_assert_eq!(plain.to_string(), indexed.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "paging")]
+mod paging {
+    use super::*;
+    use online::paging::*;
+
+    // a request misses exactly when it costs 1, and the first k distinct
+    // pages always miss
+    fn check<A: OnlineAlgorithm<Request = usize, Cost = usize, Error = Infallible>>(mut cache: A, k: usize, requests: &[usize]) -> Result<(), TestCaseError> {
+        let costs = run(&mut cache, requests)?.into_iter().map(Result::unwrap);
+        let mut seen = HashSet::new();
+        for (i, (cost, page)) in costs.zip(requests).enumerate() {
+            prop_assert!(cost <= 1, "request {} cost {}", i + 1, cost);
+            if seen.insert(*page) && seen.len() <= k {
+                prop_assert_eq!(cost, 1, "request {} is a compulsory miss", i + 1);
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn fifo(k in 1..8usize, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(FIFO::new(k), k, &requests)?;
+        }
+
+        #[test]
+        fn fwf(k in 1..8usize, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(FWF::new(k), k, &requests)?;
+        }
+
+        #[test]
+        fn lru(k in 1..8usize, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(LRU::new(k), k, &requests)?;
+        }
+
+        #[test]
+        fn lfu(k in 1..8usize, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(LFU::new(k), k, &requests)?;
+        }
+
+        #[test]
+        fn rand(k in 1..8usize, seed: u64, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(RAND::with_seed(k, seed), k, &requests)?;
+        }
+
+        #[test]
+        fn rma(k in 1..8usize, seed: u64, requests in prop::collection::vec(1..16usize, 0..200)) {
+            check(RMA::with_seed(k, seed), k, &requests)?;
+        }
+    }
+}
+
+#[cfg(feature = "bin-packing")]
+mod bin_packing {
+    use super::*;
+    use online::bin_packing::*;
+
+    // mostly valid sizes, with the odd one outside [0, 1] that must be rejected
+    fn items() -> impl Strategy<Value = Vec<f64>> {
+        prop::collection::vec(prop_oneof![9 => 0.0..=1.0f64, 1 => 1.001..2.0f64], 0..200)
+    }
+
+    fn check<A: OnlineAlgorithm<Request = f64, Cost = usize, Error = PackError> + Packer<f64>>(mut packer: A, items: &[f64]) -> Result<(), TestCaseError> {
+        let mut opened = 0;
+        for (i, (result, item)) in run(&mut packer, items)?.into_iter().zip(items).enumerate() {
+            match result {
+                Ok(cost) => {
+                    prop_assert!(cost <= 1, "item {} opened {} bins", i + 1, cost);
+                    opened += cost;
+                }
+                Err(e) => {
+                    prop_assert!(*item > 1.0, "item {} of size {} was rejected", i + 1, item);
+                    prop_assert_eq!(e, PackError::InvalidSize);
+                }
+            }
+        }
+        // the bins cannot hold less than the total size of the items
+        let total = items.iter().filter(|&&item| item <= 1.0).sum::<f64>();
+        prop_assert!(opened as f64 >= total - 1e-9, "{} bins for items of total size {}", opened, total);
+
+        for &item in items {
+            let _ = packer.serve(item);
+        }
+        prop_assert_eq!(packer.no_bins(), opened, "the bins opened after a reset differ");
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn next_fit(items in items()) {
+            check(NextFit::new(), &items)?;
+        }
+
+        #[test]
+        fn random_fit(seed: u64, items in items()) {
+            // the bins differ between runs, so only the invariants are compared
+            let mut packer = RandomFit::with_seed(seed);
+            run(&mut packer, &items)?;
+        }
+
+        #[test]
+        fn first_fit(items in items()) {
+            check(FirstFit::new(), &items)?;
+        }
+
+        #[test]
+        fn best_fit(items in items()) {
+            check(BestFit::new(), &items)?;
+        }
+
+        #[test]
+        fn worst_fit(items in items()) {
+            check(WorstFit::new(), &items)?;
+        }
+    }
+}
+
+#[cfg(feature = "migration")]
+mod migration {
+    use super::*;
+    use metricgraph::*;
+    use online::migration::*;
+
+    fn graph(hypercube: bool) -> Box<dyn MetricGraph> {
+        if hypercube { Box::new(Torus::<6>::new(2)) } else { Box::new(Torus::<3>::new(4)) }
+    }
+
+    // both graphs have 64 nodes; requests from further away are rejected
+    fn sources() -> impl Strategy<Value = Vec<usize>> {
+        prop::collection::vec(prop_oneof![19 => 0..64usize, 1 => 64..80usize], 0..200)
+    }
+
+    fn check<A: OnlineAlgorithm<Request = usize, Cost = usize>>(mut algorithm: A, requests: &[usize]) -> Result<(), TestCaseError> {
+        for (i, (result, source)) in run(&mut algorithm, requests)?.iter().zip(requests).enumerate() {
+            prop_assert_eq!(result.is_ok(), *source < 64, "request {} from node {}", i + 1, source);
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn move_to_min(hypercube: bool, d in 1..8usize, requests in sources()) {
+            check(MoveToMin::new(graph(hypercube), 0, d), &requests)?;
+        }
+
+        #[test]
+        fn coin_flip(hypercube: bool, d in 1..8usize, seed: u64, requests in sources()) {
+            check(CoinFlip::with_seed(graph(hypercube), 0, d, seed), &requests)?;
+        }
+    }
+}
+
+#[cfg(feature = "allocation")]
+mod allocation {
+    use super::*;
+    use online::allocation::*;
+
+    fn accesses(nodes: usize) -> impl Strategy<Value = Vec<Access>> {
+        prop::collection::vec(
+            (0..nodes, any::<bool>()).prop_map(|(node, write)| if write { Access::Write(node) } else { Access::Read(node) }),
+            0..200,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn count((nodes, requests) in (1..12usize).prop_flat_map(|nodes| (Just(nodes), accesses(nodes))), d in 1..6usize) {
+            let mut count = Count::new(nodes, 0, d);
+            run(&mut count, &requests)?;
+
+            // a read costs at most one remote access, a write one per other replica
+            for (i, &access) in requests.iter().enumerate() {
+                let replicas = count.no_pages();
+                let cost = count.serve(access).unwrap();
+                match access {
+                    Access::Read(_) => prop_assert!(cost <= 1, "read {} cost {}", i + 1, cost),
+                    Access::Write(_) => prop_assert!(cost <= replicas, "write {} cost {} with {} replicas", i + 1, cost, replicas),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(any(feature = "list-update", feature = "paging", feature = "bin-packing", feature = "migration"))]
+
+use online::algorithm::OnlineAlgorithm;
+use online::tape::{Choice, Coins, Randomized, Tape};
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use std::fmt;
+
+// the cost and the displayed state after each request, over two runs
+// separated by a reset
+fn run<A>(algorithm: &mut A, requests: &[A::Request]) -> Vec<(Option<A::Cost>, String)>
+where A: OnlineAlgorithm + fmt::Display, A::Request: Clone
+{
+    let mut steps = Vec::new();
+    for round in 0..2 {
+        if round > 0 {
+            algorithm.reset();
+        }
+        for request in requests {
+            let cost = algorithm.serve(request.clone()).ok();
+            steps.push((cost, algorithm.to_string()));
+        }
+    }
+    steps
+}
+
+// records a seeded run, passes its tape through the text format and replays
+// it: every cost and every state must come out the same
+fn round_trip<A>(make: impl Fn(Coins) -> A, seed: u64, requests: &[A::Request]) -> Result<(), TestCaseError>
+where A: OnlineAlgorithm + Randomized + fmt::Display, A::Request: Clone, A::Cost: PartialEq + fmt::Debug
+{
+    let mut recorded = make(Coins::seeded(seed).recording());
+    let expected = run(&mut recorded, requests);
+    let tape = recorded.coins().tape().expect("a recording keeps its tape").clone();
+
+    let mut text = Vec::new();
+    tape.write(&mut text).unwrap();
+    let read = Tape::read(text.as_slice()).unwrap();
+    prop_assert_eq!(&read, &tape);
+
+    let mut replayed = make(Coins::replaying(read));
+    prop_assert_eq!(run(&mut replayed, requests), expected);
+
+    // recording does not change the decisions drawn from the seed
+    let mut plain = make(Coins::seeded(seed));
+    prop_assert_eq!(run(&mut plain, requests), run(&mut make(Coins::seeded(seed).recording()), requests));
+    Ok(())
+}
+
+#[cfg(feature = "list-update")]
+mod list_update {
+    use super::*;
+    use online::dynlist::*;
+    use online::indexed::*;
+
+    proptest! {
+        #[test]
+        fn bit(seed: u64, requests in prop::collection::vec(1..10usize, 0..100)) {
+            round_trip(BITList::with_coins, seed, &requests)?;
+        }
+
+        #[test]
+        fn comb(seed: u64, requests in prop::collection::vec(1..10usize, 0..100)) {
+            round_trip(COMBList::with_coins, seed, &requests)?;
+        }
+
+        #[test]
+        fn indexed_bit(seed: u64, requests in prop::collection::vec(1..10usize, 0..100)) {
+            round_trip(IndexedBITList::with_coins, seed, &requests)?;
+        }
+
+        #[test]
+        fn indexed_comb(seed: u64, requests in prop::collection::vec(1..10usize, 0..100)) {
+            round_trip(IndexedCOMBList::with_coins, seed, &requests)?;
+        }
+    }
+}
+
+#[cfg(feature = "paging")]
+mod paging {
+    use super::*;
+    use online::paging::*;
+
+    proptest! {
+        #[test]
+        fn rand(k in 1..6usize, seed: u64, requests in prop::collection::vec(1..12usize, 0..100)) {
+            round_trip(|coins| RAND::with_coins(k, coins), seed, &requests)?;
+        }
+
+        #[test]
+        fn rma(k in 1..6usize, seed: u64, requests in prop::collection::vec(1..12usize, 0..100)) {
+            round_trip(|coins| RMA::with_coins(k, coins), seed, &requests)?;
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the tape ends after 0 choices, but the run asks for one of 1 options")]
+    fn short_tape() {
+        let mut cache = RAND::with_coins(1, Coins::replaying(Tape::new()));
+        cache.serve(1).unwrap();
+        cache.serve(2).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "choice 1 on the tape is heads, but the run asks for one of 1 options")]
+    fn wrong_choice() {
+        let tape = Tape { choices: vec![Choice::Flip(true)] };
+        let mut cache = RAND::with_coins(1, Coins::replaying(tape));
+        cache.serve(1).unwrap();
+        cache.serve(2).unwrap();
+    }
+}
+
+#[cfg(feature = "bin-packing")]
+mod bin_packing {
+    use super::*;
+    use online::bin_packing::*;
+
+    proptest! {
+        #[test]
+        fn random_fit(seed: u64, items in prop::collection::vec((1..=16u32).prop_map(|n| n as f64 / 16.0), 0..100)) {
+            round_trip(RandomFit::with_coins, seed, &items)?;
+        }
+    }
+}
+
+#[cfg(feature = "migration")]
+mod migration {
+    use super::*;
+    use metricgraph::*;
+    use online::migration::*;
+
+    proptest! {
+        #[test]
+        fn coin_flip(d in 1..4usize, seed: u64, requests in prop::collection::vec(0..64usize, 0..100)) {
+            round_trip(|coins| CoinFlip::with_coins(Box::new(Torus::<3>::new(4)), 0, d, coins), seed, &requests)?;
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use metricgraph::*;
+use numpy::{IntoPyArray, PyArray1};
+use online::allocation::*;
+use online::bin_packing::*;
+use online::dynlist::*;
+use online::migration::*;
+use online::paging::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fmt;
+
+pub trait List: Dynlist<usize> + fmt::Display {}
+impl<T: Dynlist<usize> + fmt::Display> List for T {}
+
+pub trait Cache: Cacher<usize> + fmt::Display {}
+impl<T: Cacher<usize> + fmt::Display> Cache for T {}
+
+pub trait Bins: Packer<f64> + fmt::Display {}
+impl<T: Packer<f64> + fmt::Display> Bins for T {}
+
+pub trait Migration: PageMigration + fmt::Display {}
+impl<T: PageMigration + fmt::Display> Migration for T {}
+
+pub fn invalid(msg: String) -> PyErr {
+    PyValueError::new_err(msg)
+}
+
+// randomized algorithms draw from entropy unless given a seed
+pub fn new_list(name: &str, seed: Option<u64>) -> PyResult<Box<dyn List>> {
+    Ok(match (name, seed) {
+        ("Simple", _) => Box::new(SimpleList::new()),
+        ("TP", _) => Box::new(TPList::new()),
+        ("MTF", _) => Box::new(MTFList::new()),
+        ("FC", _) => Box::new(FCList::new()),
+        ("TS", _) => Box::new(TSList::new()),
+        ("BIT", None) => Box::new(BITList::new()),
+        ("BIT", Some(seed)) => Box::new(BITList::with_seed(seed)),
+        ("COMB", None) => Box::new(COMBList::new()),
+        ("COMB", Some(seed)) => Box::new(COMBList::with_seed(seed)),
+        _ => return Err(invalid(format!("unknown list: {} (expected Simple, TP, MTF, FC, TS, BIT or COMB)", name))),
+    })
+}
+
+pub fn new_cache(name: &str, k: usize, seed: Option<u64>) -> PyResult<Box<dyn Cache>> {
+    if k == 0 {
+        return Err(invalid("cache size must be positive".to_string()));
+    }
+    Ok(match (name, seed) {
+        ("FIFO", _) => Box::new(FIFO::new(k)),
+        ("FWF", _) => Box::new(FWF::new(k)),
+        ("LRU", _) => Box::new(LRU::new(k)),
+        ("LFU", _) => Box::new(LFU::new(k)),
+        ("RAND", None) => Box::new(RAND::new(k)),
+        ("RAND", Some(seed)) => Box::new(RAND::with_seed(k, seed)),
+        ("RMA", None) => Box::new(RMA::new(k)),
+        ("RMA", Some(seed)) => Box::new(RMA::with_seed(k, seed)),
+        _ => return Err(invalid(format!("unknown cache: {} (expected FIFO, FWF, LRU, LFU, RAND or RMA)", name))),
+    })
+}
+
+pub fn new_packer(name: &str, seed: Option<u64>) -> PyResult<Box<dyn Bins>> {
+    Ok(match (name, seed) {
+        ("NF", _) => Box::new(NextFit::new()),
+        ("RF", None) => Box::new(RandomFit::new()),
+        ("RF", Some(seed)) => Box::new(RandomFit::with_seed(seed)),
+        ("FF", _) => Box::new(FirstFit::new()),
+        ("BF", _) => Box::new(BestFit::new()),
+        ("WF", _) => Box::new(WorstFit::new()),
+        _ => return Err(invalid(format!("unknown packer: {} (expected NF, RF, FF, BF or WF)", name))),
+    })
+}
+
+pub fn new_graph(name: &str) -> PyResult<Box<dyn MetricGraph>> {
+    Ok(match name {
+        "torus3d" => Box::new(Torus::<3>::new(4)),
+        "hypercube" => Box::new(Torus::<6>::new(2)),
+        _ => return Err(invalid(format!("unknown graph: {} (expected torus3d or hypercube)", name))),
+    })
+}
+
+pub fn new_migration(name: &str, graph: &str, d: usize, seed: Option<u64>) -> PyResult<Box<dyn Migration>> {
+    let graph = new_graph(graph)?;
+    Ok(match (name, seed) {
+        ("MoveToMin", _) => Box::new(MoveToMin::new(graph, 0, d)),
+        ("CoinFlip", None) => Box::new(CoinFlip::new(graph, 0, d)),
+        ("CoinFlip", Some(seed)) => Box::new(CoinFlip::with_seed(graph, 0, d, seed)),
+        _ => return Err(invalid(format!("unknown migration algorithm: {} (expected MoveToMin or CoinFlip)", name))),
+    })
+}
+
+fn index_error(node: usize) -> PyErr {
+    invalid(format!("no node {}", node))
+}
+
+#[pyclass(name = "List", unsendable)]
+pub struct PyList {
+    pub name: String,
+    list: Box<dyn List>,
+}
+
+#[pymethods]
+impl PyList {
+    #[new]
+    #[pyo3(signature = (name, seed=None))]
+    fn new(name: &str, seed: Option<u64>) -> PyResult<Self> {
+        Ok(Self { name: name.to_string(), list: new_list(name, seed)? })
+    }
+
+    // the cost of an access is the 1-based position the element was found at
+    fn access(&mut self, item: usize) -> usize {
+        self.list.access(item)
+    }
+
+    // serves every request and returns the cost of each
+    fn run<'py>(&mut self, py: Python<'py>, requests: Vec<usize>) -> Bound<'py, PyArray1<usize>> {
+        requests.into_iter().map(|r| self.list.access(r)).collect::<Vec<_>>().into_pyarray(py)
+    }
+
+    fn __str__(&self) -> String {
+        self.list.to_string()
+    }
+}
+
+#[pyclass(name = "Cache", unsendable)]
+pub struct PyCache {
+    pub name: String,
+    pub k: usize,
+    cache: Box<dyn Cache>,
+}
+
+#[pymethods]
+impl PyCache {
+    #[new]
+    #[pyo3(signature = (name, k, seed=None))]
+    fn new(name: &str, k: usize, seed: Option<u64>) -> PyResult<Self> {
+        Ok(Self { name: name.to_string(), k, cache: new_cache(name, k, seed)? })
+    }
+
+    // pages are numbered from 1; the cost is 1 on a miss and 0 on a hit
+    fn access(&mut self, page: usize) -> PyResult<usize> {
+        if page == 0 {
+            return Err(invalid("pages are numbered from 1".to_string()));
+        }
+        Ok(self.cache.access(page))
+    }
+
+    fn run<'py>(&mut self, py: Python<'py>, requests: Vec<usize>) -> PyResult<Bound<'py, PyArray1<usize>>> {
+        let costs = requests.into_iter().map(|r| self.access(r)).collect::<PyResult<Vec<_>>>()?;
+        Ok(costs.into_pyarray(py))
+    }
+
+    fn __str__(&self) -> String {
+        self.cache.to_string()
+    }
+}
+
+#[pyclass(name = "Packer", unsendable)]
+pub struct PyPacker {
+    pub name: String,
+    packer: Box<dyn Bins>,
+}
+
+#[pymethods]
+impl PyPacker {
+    #[new]
+    #[pyo3(signature = (name, seed=None))]
+    fn new(name: &str, seed: Option<u64>) -> PyResult<Self> {
+        Ok(Self { name: name.to_string(), packer: new_packer(name, seed)? })
+    }
+
+    fn pack(&mut self, item: f64) -> PyResult<()> {
+        self.packer.pack(item).map_err(|_| invalid(format!("item size {} is outside [0, 1]", item)))
+    }
+
+    // packs every item and returns the number of bins after each one
+    fn run<'py>(&mut self, py: Python<'py>, items: Vec<f64>) -> PyResult<Bound<'py, PyArray1<usize>>> {
+        let bins = items
+            .into_iter()
+            .map(|item| self.pack(item).map(|_| self.packer.no_bins()))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(bins.into_pyarray(py))
+    }
+
+    #[getter]
+    fn no_bins(&self) -> usize {
+        self.packer.no_bins()
+    }
+
+    fn __str__(&self) -> String {
+        self.packer.to_string()
+    }
+}
+
+#[pyclass(name = "Migration", unsendable)]
+pub struct PyMigration {
+    pub name: String,
+    pub graph: String,
+    pub d: usize,
+    size: usize,
+    algorithm: Box<dyn Migration>,
+}
+
+#[pymethods]
+impl PyMigration {
+    #[new]
+    #[pyo3(signature = (name, graph, d, seed=None))]
+    fn new(name: &str, graph: &str, d: usize, seed: Option<u64>) -> PyResult<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            graph: graph.to_string(),
+            d,
+            size: new_graph(graph)?.size(),
+            algorithm: new_migration(name, graph, d, seed)?,
+        })
+    }
+
+    // nodes are numbered from 0
+    fn read(&mut self, node: usize) -> PyResult<usize> {
+        self.algorithm.read(node).map_err(|_| index_error(node))
+    }
+
+    fn run<'py>(&mut self, py: Python<'py>, requests: Vec<usize>) -> PyResult<Bound<'py, PyArray1<usize>>> {
+        let costs = requests.into_iter().map(|r| self.read(r)).collect::<PyResult<Vec<_>>>()?;
+        Ok(costs.into_pyarray(py))
+    }
+
+    #[getter]
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn __str__(&self) -> String {
+        self.algorithm.to_string()
+    }
+}
+
+#[pyclass(name = "Allocation", unsendable)]
+pub struct PyAllocation {
+    pub nodes: usize,
+    pub d: usize,
+    count: Count,
+}
+
+impl PyAllocation {
+    fn check(&self, node: usize) -> PyResult<()> {
+        if node < self.nodes { Ok(()) } else { Err(index_error(node)) }
+    }
+}
+
+#[pymethods]
+impl PyAllocation {
+    #[new]
+    fn new(nodes: usize, d: usize) -> PyResult<Self> {
+        if nodes == 0 {
+            return Err(invalid("the number of nodes must be positive".to_string()));
+        }
+        Ok(Self { nodes, d, count: Count::new(nodes, 0, d) })
+    }
+
+    fn read(&mut self, node: usize) -> PyResult<usize> {
+        self.check(node)?;
+        self.count.read(node).map_err(|_| index_error(node))
+    }
+
+    fn write(&mut self, node: usize) -> PyResult<usize> {
+        self.check(node)?;
+        self.count.write(node).map_err(|_| index_error(node))
+    }
+
+    // serves reads, or writes where `writes` is true, and returns the cost of
+    // each request and the number of replicas after it
+    #[allow(clippy::type_complexity)]
+    fn run<'py>(
+        &mut self,
+        py: Python<'py>,
+        requests: Vec<usize>,
+        writes: Vec<bool>,
+    ) -> PyResult<(Bound<'py, PyArray1<usize>>, Bound<'py, PyArray1<usize>>)> {
+        if requests.len() != writes.len() {
+            return Err(invalid("requests and writes differ in length".to_string()));
+        }
+        let mut costs = Vec::with_capacity(requests.len());
+        let mut pages = Vec::with_capacity(requests.len());
+        for (node, write) in requests.into_iter().zip(writes) {
+            costs.push(if write { self.write(node)? } else { self.read(node)? });
+            pages.push(self.count.no_pages());
+        }
+        Ok((costs.into_pyarray(py), pages.into_pyarray(py)))
+    }
+
+    #[getter]
+    fn no_pages(&self) -> usize {
+        self.count.no_pages()
+    }
+
+    fn __str__(&self) -> String {
+        self.count.to_string()
+    }
+}
@@ -0,0 +1,82 @@
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+
+use crate::algorithms::invalid;
+
+// the workloads the experiments draw requests from
+#[derive(Debug, Copy, Clone)]
+pub enum Workload {
+    Uniform, Harmonic, Biharmonic, Geometric,
+}
+
+impl Workload {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "uniform" => Ok(Workload::Uniform),
+            "harmonic" => Ok(Workload::Harmonic),
+            "biharmonic" => Ok(Workload::Biharmonic),
+            "geometric" => Ok(Workload::Geometric),
+            _ => Err(invalid(format!("unknown workload: {} (expected uniform, harmonic, biharmonic or geometric)", name))),
+        }
+    }
+
+    pub fn generate(&self, g: &mut distributions::Generator) -> usize {
+        match self {
+            Workload::Uniform => g.uniform(),
+            Workload::Harmonic => g.harmonic(),
+            Workload::Biharmonic => g.biharmonic(),
+            Workload::Geometric => g.geometric(),
+        }
+    }
+}
+
+#[pyclass(name = "Generator", unsendable)]
+pub struct PyGenerator {
+    g: distributions::Generator,
+}
+
+#[pymethods]
+impl PyGenerator {
+    #[new]
+    #[pyo3(signature = (n, seed=None))]
+    fn new(n: usize, seed: Option<u64>) -> PyResult<Self> {
+        if n == 0 {
+            return Err(invalid("n must be positive".to_string()));
+        }
+        let g = match seed {
+            Some(seed) => distributions::Generator::with_seed(n, seed),
+            None => distributions::Generator::new(n),
+        };
+        Ok(Self { g })
+    }
+
+    fn uniform(&mut self) -> usize {
+        self.g.uniform()
+    }
+
+    fn harmonic(&mut self) -> usize {
+        self.g.harmonic()
+    }
+
+    fn biharmonic(&mut self) -> usize {
+        self.g.biharmonic()
+    }
+
+    fn geometric(&mut self) -> usize {
+        self.g.geometric()
+    }
+
+    fn std(&mut self) -> f64 {
+        self.g.std()
+    }
+
+    fn bernoulli(&mut self, p: f64) -> usize {
+        self.g.bernoulli(p)
+    }
+
+    // `count` draws from one of the workloads in 1..=n
+    fn sample<'py>(&mut self, py: Python<'py>, workload: &str, count: usize) -> PyResult<Bound<'py, PyArray1<usize>>> {
+        let workload = Workload::parse(workload)?;
+        Ok((0..count).map(|_| workload.generate(&mut self.g)).collect::<Vec<_>>().into_pyarray(py))
+    }
+}
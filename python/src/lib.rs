@@ -0,0 +1,17 @@
+mod algorithms;
+mod generator;
+mod simulate;
+
+use pyo3::prelude::*;
+
+#[pymodule]
+fn pyonline(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<generator::PyGenerator>()?;
+    m.add_class::<algorithms::PyList>()?;
+    m.add_class::<algorithms::PyCache>()?;
+    m.add_class::<algorithms::PyPacker>()?;
+    m.add_class::<algorithms::PyMigration>()?;
+    m.add_class::<algorithms::PyAllocation>()?;
+    m.add_function(wrap_pyfunction!(simulate::simulate, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use distributions::Generator;
+use online::allocation::*;
+use numpy::IntoPyArray;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use runner::config::parse_sampling;
+use runner::stats::{Curve, Samples};
+
+use crate::algorithms::*;
+use crate::generator::Workload;
+
+// what to rebuild for every repetition, taken from an algorithm object
+enum Problem {
+    List(String),
+    Cache(String, usize),
+    Packer(String),
+    Migration(String, String, usize),
+    Allocation(usize, usize),
+}
+
+impl Problem {
+    fn from_object(algorithm: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(list) = algorithm.cast::<PyList>() {
+            return Ok(Problem::List(list.borrow().name.clone()));
+        }
+        if let Ok(cache) = algorithm.cast::<PyCache>() {
+            let cache = cache.borrow();
+            return Ok(Problem::Cache(cache.name.clone(), cache.k));
+        }
+        if let Ok(packer) = algorithm.cast::<PyPacker>() {
+            return Ok(Problem::Packer(packer.borrow().name.clone()));
+        }
+        if let Ok(migration) = algorithm.cast::<PyMigration>() {
+            let migration = migration.borrow();
+            return Ok(Problem::Migration(migration.name.clone(), migration.graph.clone(), migration.d));
+        }
+        if let Ok(allocation) = algorithm.cast::<PyAllocation>() {
+            let allocation = allocation.borrow();
+            return Ok(Problem::Allocation(allocation.nodes, allocation.d));
+        }
+        Err(invalid("expected a List, Cache, Packer, Migration or Allocation".to_string()))
+    }
+
+    // the range requests are drawn from, as in the experiment binaries
+    fn elements(&self, n: Option<usize>) -> PyResult<usize> {
+        let nodes = match self {
+            Problem::List(_) | Problem::Cache(..) => return Ok(n.unwrap_or(100)),
+            Problem::Packer(_) => return Ok(n.unwrap_or(10)),
+            Problem::Migration(_, graph, _) => new_graph(graph)?.size(),
+            Problem::Allocation(nodes, _) => *nodes,
+        };
+        match n {
+            Some(n) if n > nodes => Err(invalid(format!("n is larger than the {} nodes", nodes))),
+            _ => Ok(n.unwrap_or(nodes)),
+        }
+    }
+}
+
+struct Settings {
+    workload: Workload,
+    requests: usize,
+    reps: usize,
+    write_probability: f64,
+}
+
+// the repetitions of list1..list5, run by the same loops as the binaries
+fn measure(problem: &Problem, settings: &Settings, g: &mut Generator, seeds: &mut Option<StdRng>, curves: &mut [Curve]) -> PyResult<()> {
+    let requests = settings.requests;
+    let generate = |g: &mut Generator| settings.workload.generate(g);
+    for _ in 0..settings.reps {
+        let seed = seeds.as_mut().map(|rng| rng.next_u64());
+        match problem {
+            Problem::List(name) => list1::repetition(new_list(name, seed)?.as_mut(), g, generate, requests, &mut curves[0]),
+            Problem::Cache(name, k) => list2::repetition(new_cache(name, *k, seed)?.as_mut(), g, generate, requests, &mut curves[0]),
+            Problem::Packer(name) => list3::repetition(new_packer(name, seed)?.as_mut(), g, generate, requests, &mut curves[0]),
+            Problem::Migration(name, graph, d) => {
+                list4::repetition(new_migration(name, graph, *d, seed)?.as_mut(), g, generate, requests, &mut curves[0])
+            }
+            Problem::Allocation(nodes, d) => {
+                let [cost, pages] = curves else {
+                    unreachable!("allocation records cost and pages");
+                };
+                let mut algorithm = Count::new(*nodes, 0, *d);
+                list5::repetition(&mut algorithm, g, generate, settings.write_probability, requests, cost, pages);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_dict<'py>(py: Python<'py>, samples: Samples) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    let column = |f: fn(&runner::stats::Summary) -> f64| samples.iter().map(|(_, s)| f(s)).collect::<Vec<_>>();
+    dict.set_item("step", samples.iter().map(|(step, _)| *step).collect::<Vec<_>>().into_pyarray(py))?;
+    dict.set_item("reps", samples.iter().map(|(_, s)| s.reps).collect::<Vec<_>>().into_pyarray(py))?;
+    dict.set_item("mean", column(|s| s.mean).into_pyarray(py))?;
+    dict.set_item("variance", column(|s| s.variance).into_pyarray(py))?;
+    dict.set_item("std_err", column(|s| s.std_err).into_pyarray(py))?;
+    dict.set_item("ci_low", column(|s| s.ci_low).into_pyarray(py))?;
+    dict.set_item("ci_high", column(|s| s.ci_high).into_pyarray(py))?;
+    dict.set_item("median", column(|s| s.median).into_pyarray(py))?;
+    dict.set_item("p95", column(|s| s.p95).into_pyarray(py))?;
+    Ok(dict)
+}
+
+// runs `reps` repetitions of `requests` requests against fresh copies of
+// `algorithm` and returns, per metric, the summary of its curve at the sampled
+// steps; a seed makes the workload and the randomized algorithms reproducible
+#[pyfunction]
+#[pyo3(signature = (algorithm, workload, requests, reps, n=None, seed=None, sample="log:20", write_probability=0.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate<'py>(
+    py: Python<'py>,
+    algorithm: &Bound<'py, PyAny>,
+    workload: &str,
+    requests: usize,
+    reps: usize,
+    n: Option<usize>,
+    seed: Option<u64>,
+    sample: &str,
+    write_probability: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let problem = Problem::from_object(algorithm)?;
+    let n = problem.elements(n)?;
+    if n == 0 {
+        return Err(invalid("n must be positive".to_string()));
+    }
+    let settings = Settings {
+        workload: Workload::parse(workload)?,
+        requests,
+        reps,
+        write_probability,
+    };
+    let sampling = parse_sampling(sample).map_err(|e| invalid(e.to_string()))?;
+
+    let metrics: &[&str] = match problem {
+        Problem::Packer(_) => &["ratio"],
+        Problem::Allocation(..) => &["cost", "pages"],
+        _ => &["cost"],
+    };
+    let mut curves = metrics.iter().map(|_| Curve::sampled(sampling.steps(requests))).collect::<Vec<_>>();
+
+    py.detach(|| {
+        let (mut g, mut seeds) = match seed {
+            Some(seed) => (Generator::with_seed(n, seed), Some(StdRng::seed_from_u64(seed))),
+            None => (Generator::new(n), None),
+        };
+        measure(&problem, &settings, &mut g, &mut seeds, &mut curves)
+    })?;
+
+    let result = PyDict::new(py);
+    for (metric, curve) in metrics.iter().zip(curves) {
+        result.set_item(metric, to_dict(py, curve.summary())?)?;
+    }
+    Ok(result)
+}
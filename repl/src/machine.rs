@@ -0,0 +1,246 @@
+use metricgraph::*;
+use online::allocation::*;
+use online::bin_packing::*;
+use online::dynlist::*;
+use online::events::Observer;
+use online::migration::*;
+use online::paging::*;
+use online::tape::{Coins, Randomized};
+use std::fmt;
+
+// one algorithm instance behind a textual request interface
+pub trait Machine {
+    // serves one request, returning it in canonical form together with its cost
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String>;
+    fn state(&self) -> String;
+    // where a randomized algorithm takes its decisions from
+    fn coins(&self) -> Option<&Coins> {
+        None
+    }
+}
+
+// builds a fresh machine taking its decisions from the coins, so that a
+// session can be replayed exactly
+pub type Factory = Box<dyn Fn(Coins) -> Box<dyn Machine>>;
+
+trait List: Dynlist<usize> + fmt::Display {
+    fn coins(&self) -> Option<&Coins> {
+        None
+    }
+}
+
+trait Cache: Cacher<usize> + fmt::Display {
+    fn coins(&self) -> Option<&Coins> {
+        None
+    }
+}
+
+trait Bins: Packer<f64> + fmt::Display {
+    fn coins(&self) -> Option<&Coins> {
+        None
+    }
+}
+
+trait Migration: PageMigration + fmt::Display {
+    fn coins(&self) -> Option<&Coins> {
+        None
+    }
+}
+
+// sorts the algorithms of each problem into deterministic and randomized ones
+macro_rules! algorithms {
+    ($problem:ident: $($deterministic:ty),*; randomized $($randomized:ty),*) => {
+        $(impl $problem for $deterministic {})*
+        $(impl $problem for $randomized {
+            fn coins(&self) -> Option<&Coins> {
+                Some(Randomized::coins(self))
+            }
+        })*
+    };
+}
+
+algorithms!(List: SimpleList<usize>, TPList<usize>, MTFList<usize>, FCList<usize>, TSList<usize>; randomized BITList<usize>, COMBList<usize>);
+algorithms!(Cache: FIFO, FWF, LRU, LFU; randomized RAND, RMA);
+algorithms!(Bins: NextFit, FirstFit, BestFit, WorstFit; randomized RandomFit);
+algorithms!(Migration: MoveToMin; randomized CoinFlip);
+
+fn number<T: std::str::FromStr>(text: &str, what: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("expected {}, got {}", what, text))
+}
+
+struct ListUpdate(Box<dyn List>);
+
+impl Machine for ListUpdate {
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String> {
+        let item: usize = number(request, "an element")?;
+        Ok((item.to_string(), self.0.access_observed(item, observer)))
+    }
+
+    fn state(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn coins(&self) -> Option<&Coins> {
+        self.0.coins()
+    }
+}
+
+struct Paging(Box<dyn Cache>);
+
+impl Machine for Paging {
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String> {
+        // page 0 marks an empty slot inside the caches
+        let page: usize = number(request, "a page")?;
+        if page == 0 {
+            return Err("pages are numbered from 1".to_string());
+        }
+        Ok((page.to_string(), self.0.access_observed(page, observer)))
+    }
+
+    fn state(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn coins(&self) -> Option<&Coins> {
+        self.0.coins()
+    }
+}
+
+struct BinPacking {
+    packer: Box<dyn Bins>,
+    items: f64,
+}
+
+impl Machine for BinPacking {
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String> {
+        let item: f64 = number(request, "an item size")?;
+        let before = self.packer.no_bins();
+        self.packer.pack_observed(item, observer).map_err(|_| format!("item size {} is outside [0, 1]", item))?;
+        self.items += item;
+        Ok((item.to_string(), self.packer.no_bins() - before))
+    }
+
+    fn state(&self) -> String {
+        format!("{}, total item size {:.3}", self.packer, self.items)
+    }
+
+    fn coins(&self) -> Option<&Coins> {
+        self.packer.coins()
+    }
+}
+
+struct Migrating(Box<dyn Migration>);
+
+impl Machine for Migrating {
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String> {
+        let node: usize = number(request, "a node")?;
+        let cost = self.0.read_observed(node, observer).map_err(|_| format!("no node {}", node))?;
+        Ok((node.to_string(), cost))
+    }
+
+    fn state(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn coins(&self) -> Option<&Coins> {
+        self.0.coins()
+    }
+}
+
+struct Allocation {
+    count: Count,
+    nodes: usize,
+}
+
+impl Machine for Allocation {
+    // "r3" or "3" reads at node 3, "w3" writes there
+    fn serve(&mut self, request: &str, observer: &mut dyn Observer) -> Result<(String, usize), String> {
+        let (write, node) = match request.strip_prefix('w') {
+            Some(node) => (true, node),
+            None => (false, request.strip_prefix('r').unwrap_or(request)),
+        };
+        let node: usize = number(node, "r<node> or w<node>")?;
+        if node >= self.nodes {
+            return Err(format!("no node {}", node));
+        }
+
+        let cost = if write {
+            self.count.write_observed(node, observer)
+        } else {
+            self.count.read_observed(node, observer)
+        };
+        let canonical = format!("{}{}", if write { "w" } else { "r" }, node);
+        Ok((canonical, cost.map_err(|_| format!("no node {}", node))?))
+    }
+
+    fn state(&self) -> String {
+        self.count.to_string()
+    }
+}
+
+pub const PROBLEMS: &str = "  list_update Simple|TP|MTF|FC|TS|BIT|COMB
+  paging FIFO|FWF|LRU|LFU|RAND|RMA <k>
+  bin_packing NF|RF|FF|BF|WF
+  page_migration MoveToMin|CoinFlip torus3d|hypercube <d>
+  page_allocation Count <nodes> <d>";
+
+pub fn factory(problem: &str, algorithm: &str, params: &[String]) -> Result<Factory, String> {
+    let param = |i: usize, what: &str| -> Result<usize, String> {
+        let value = params.get(i).ok_or_else(|| format!("{} {} needs {}", problem, algorithm, what))?;
+        number::<usize>(value, what).and_then(|v| if v > 0 { Ok(v) } else { Err(format!("{} must be positive", what)) })
+    };
+
+    let factory: Factory = match (problem, algorithm) {
+        ("list_update", "Simple") => Box::new(|_| Box::new(ListUpdate(Box::new(SimpleList::new())))),
+        ("list_update", "TP") => Box::new(|_| Box::new(ListUpdate(Box::new(TPList::new())))),
+        ("list_update", "MTF") => Box::new(|_| Box::new(ListUpdate(Box::new(MTFList::new())))),
+        ("list_update", "FC") => Box::new(|_| Box::new(ListUpdate(Box::new(FCList::new())))),
+        ("list_update", "TS") => Box::new(|_| Box::new(ListUpdate(Box::new(TSList::new())))),
+        ("list_update", "BIT") => Box::new(|coins| Box::new(ListUpdate(Box::new(BITList::with_coins(coins))))),
+        ("list_update", "COMB") => Box::new(|coins| Box::new(ListUpdate(Box::new(COMBList::with_coins(coins))))),
+        ("paging", _) => {
+            let k = param(0, "a cache size k")?;
+            match algorithm {
+                "FIFO" => Box::new(move |_| Box::new(Paging(Box::new(FIFO::new(k))))),
+                "FWF" => Box::new(move |_| Box::new(Paging(Box::new(FWF::new(k))))),
+                "LRU" => Box::new(move |_| Box::new(Paging(Box::new(LRU::new(k))))),
+                "LFU" => Box::new(move |_| Box::new(Paging(Box::new(LFU::new(k))))),
+                "RAND" => Box::new(move |coins| Box::new(Paging(Box::new(RAND::with_coins(k, coins))))),
+                "RMA" => Box::new(move |coins| Box::new(Paging(Box::new(RMA::with_coins(k, coins))))),
+                _ => return Err(format!("unknown paging algorithm: {}", algorithm)),
+            }
+        }
+        ("bin_packing", _) => {
+            let packer: fn(Coins) -> Box<dyn Bins> = match algorithm {
+                "NF" => |_| Box::new(NextFit::new()),
+                "RF" => |coins| Box::new(RandomFit::with_coins(coins)),
+                "FF" => |_| Box::new(FirstFit::new()),
+                "BF" => |_| Box::new(BestFit::new()),
+                "WF" => |_| Box::new(WorstFit::new()),
+                _ => return Err(format!("unknown bin packing algorithm: {}", algorithm)),
+            };
+            Box::new(move |coins| Box::new(BinPacking { packer: packer(coins), items: 0.0 }))
+        }
+        ("page_migration", _) => {
+            let graph: fn() -> Box<dyn MetricGraph> = match params.first().map(|s| s.as_str()) {
+                Some("torus3d") => || Box::new(Torus::<3>::new(4)),
+                Some("hypercube") => || Box::new(Torus::<6>::new(2)),
+                _ => return Err("page_migration needs a graph: torus3d or hypercube".to_string()),
+            };
+            let d = param(1, "a migration cost d")?;
+            match algorithm {
+                "MoveToMin" => Box::new(move |_| Box::new(Migrating(Box::new(MoveToMin::new(graph(), 0, d))))),
+                "CoinFlip" => Box::new(move |coins| Box::new(Migrating(Box::new(CoinFlip::with_coins(graph(), 0, d, coins))))),
+                _ => return Err(format!("unknown page migration algorithm: {}", algorithm)),
+            }
+        }
+        ("page_allocation", "Count") => {
+            let nodes = param(0, "a number of nodes")?;
+            let d = param(1, "a replication cost d")?;
+            Box::new(move |_| Box::new(Allocation { count: Count::new(nodes, 0, d), nodes }))
+        }
+        _ => return Err(format!("unknown problem or algorithm: {} {}", problem, algorithm)),
+    };
+
+    Ok(factory)
+}
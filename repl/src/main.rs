@@ -0,0 +1,233 @@
+use machine::{factory, Factory, Machine, PROBLEMS};
+use online::events::Event;
+use online::tape::{Coins, Tape};
+use serde_json::{json, Value};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::ExitCode;
+
+mod machine;
+
+const COMMANDS: &str = "  <requests>    serve requests separated by spaces or commas
+  state         show the current state
+  history       list the requests served so far
+  undo [n]      take back the last n requests (default 1)
+  save <file>   write the session as a JSON Lines trace
+  tape <file>   write the random choices of the algorithm, to replay with --tape
+  help          show this help
+  quit          leave";
+
+struct Step {
+    request: String,
+    cost: usize,
+    events: Vec<Event>,
+}
+
+struct Session {
+    problem: String,
+    algorithm: String,
+    params: Vec<String>,
+    seed: u64,
+    // the recorded choices a randomized algorithm replays instead of drawing
+    tape: Option<Tape>,
+    factory: Factory,
+    machine: Box<dyn Machine>,
+    history: Vec<Step>,
+}
+
+// every choice drawn from the seed is recorded, so that it can be written out
+fn coins(seed: u64, tape: Option<&Tape>) -> Coins {
+    match tape {
+        Some(tape) => Coins::replaying(tape.clone()),
+        None => Coins::seeded(seed).recording(),
+    }
+}
+
+impl Session {
+    fn total(&self) -> usize {
+        self.history.iter().map(|step| step.cost).sum()
+    }
+
+    fn serve(&mut self, request: &str) -> Result<(), String> {
+        let mut events = Vec::new();
+        let (request, cost) = if self.tape.is_some() {
+            // a replayed tape panics once the run asks for a choice it does not
+            // hold; the message becomes the error and the machine is rebuilt
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(|_| {}));
+            let served = panic::catch_unwind(AssertUnwindSafe(|| self.machine.serve(request, &mut events)));
+            panic::set_hook(hook);
+            match served {
+                Ok(served) => served?,
+                Err(payload) => {
+                    self.undo(0);
+                    return Err(payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "the run departs from the tape".to_string()));
+                }
+            }
+        } else {
+            self.machine.serve(request, &mut events)?
+        };
+        self.history.push(Step { request, cost, events });
+
+        let step = self.history.last().unwrap();
+        println!("#{} {}: cost {}, total {}", self.history.len(), step.request, step.cost, self.total());
+        if !step.events.is_empty() {
+            let events = step.events.iter().map(|e| format!("{:?}", e)).collect::<Vec<_>>();
+            println!("  events: {}", events.join(", "));
+        }
+        println!("  state: {}", self.machine.state());
+        Ok(())
+    }
+
+    // randomized algorithms draw from a seeded generator, so rebuilding the
+    // machine and replaying the remaining requests restores the earlier state
+    fn undo(&mut self, n: usize) {
+        let keep = self.history.len().saturating_sub(n);
+        self.history.truncate(keep);
+        self.machine = (self.factory)(coins(self.seed, self.tape.as_ref()));
+        for step in &self.history {
+            self.machine.serve(&step.request, &mut ()).expect("replayed request was accepted before");
+        }
+        println!("back at #{}, total {}", self.history.len(), self.total());
+        println!("  state: {}", self.machine.state());
+    }
+
+    fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = json!({ "problem": self.problem, "algorithm": self.algorithm, "params": self.params, "seed": self.seed });
+        writeln!(writer, "{}", header)?;
+        for (i, step) in self.history.iter().enumerate() {
+            let events = step.events.iter().map(|e| serde_json::from_str(&e.to_json())).collect::<Result<Vec<Value>, _>>()?;
+            writeln!(writer, "{}", json!({ "step": i + 1, "request": step.request, "cost": step.cost, "events": events }))?;
+        }
+        writer.flush()
+    }
+
+    fn write_tape(&self, path: &str) -> Result<usize, String> {
+        let tape = self.machine.coins().and_then(|coins| coins.tape()).ok_or(format!("{} makes no random choices", self.algorithm))?;
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        tape.write(BufWriter::new(file)).map_err(|e| e.to_string())?;
+        Ok(tape.choices.len())
+    }
+
+    // returns false once the session should end
+    fn command(&mut self, line: &str) -> bool {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            [] => {}
+            ["quit" | "exit"] => return false,
+            ["help"] => println!("{}", COMMANDS),
+            ["state"] => println!("{}", self.machine.state()),
+            ["history"] => {
+                for (i, step) in self.history.iter().enumerate() {
+                    println!("#{} {}: cost {}", i + 1, step.request, step.cost);
+                }
+            }
+            ["undo"] => self.undo(1),
+            ["undo", n] => match n.parse() {
+                Ok(n) => self.undo(n),
+                Err(_) => println!("undo expects a number of requests"),
+            },
+            ["save", path] => match self.save(path) {
+                Ok(()) => println!("saved {} requests to {}", self.history.len(), path),
+                Err(e) => println!("cannot save: {}", e),
+            },
+            ["tape", path] => match self.write_tape(path) {
+                Ok(n) => println!("wrote {} choices to {}", n, path),
+                Err(e) => println!("cannot write the tape: {}", e),
+            },
+            _ => {
+                for request in line.split([' ', '\t', ',']).filter(|r| !r.is_empty()) {
+                    if let Err(e) = self.serve(request) {
+                        println!("{}: {}", request, e);
+                        break;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+fn usage() -> String {
+    format!("usage: repl <problem> <algorithm> [params] [--seed N] [--tape FILE]\n{}", PROBLEMS)
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let mut seed = rand::random();
+    if let Some(i) = args.iter().position(|a| a == "--seed") {
+        match args.get(i + 1).and_then(|s| s.parse().ok()) {
+            Some(s) => seed = s,
+            None => {
+                eprintln!("--seed expects a number\n{}", usage());
+                return ExitCode::from(2);
+            }
+        }
+        args.drain(i..i + 2);
+    }
+    let mut tape = None;
+    if let Some(i) = args.iter().position(|a| a == "--tape") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--tape expects a file\n{}", usage());
+            return ExitCode::from(2);
+        };
+        match File::open(path).and_then(|file| Tape::read(BufReader::new(file))) {
+            Ok(read) => tape = Some(read),
+            Err(e) => {
+                eprintln!("cannot read the tape {}: {}", path, e);
+                return ExitCode::from(2);
+            }
+        }
+        args.drain(i..i + 2);
+    }
+    let [problem, algorithm, params @ ..] = args.as_slice() else {
+        eprintln!("{}", usage());
+        return ExitCode::from(2);
+    };
+
+    let factory = match factory(problem, algorithm, params) {
+        Ok(factory) => factory,
+        Err(e) => {
+            eprintln!("{}\n{}", e, usage());
+            return ExitCode::from(2);
+        }
+    };
+    let machine = factory(coins(seed, tape.as_ref()));
+    let mut session = Session {
+        problem: problem.clone(),
+        algorithm: algorithm.clone(),
+        params: params.to_vec(),
+        seed,
+        tape,
+        machine,
+        factory,
+        history: Vec::new(),
+    };
+
+    let name = [problem, algorithm].into_iter().chain(params).cloned().collect::<Vec<_>>().join(" ");
+    match &session.tape {
+        Some(tape) => println!("{}, replaying {} choices (type help for commands)", name, tape.choices.len()),
+        None => println!("{}, seed {} (type help for commands)", name, seed),
+    }
+    println!("  state: {}", session.machine.state());
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) if !session.command(line.trim()) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(1);
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
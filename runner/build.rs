@@ -0,0 +1,32 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+#[path = "src/provenance.rs"]
+mod provenance;
+
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=RUNNER_RUSTC_VERSION={}", version.trim());
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    // the revision the binaries are built from, not whatever is checked out
+    // where they later run; empty outside a git checkout. Watching every
+    // tracked file rebuilds the runner, and so relinks the binaries, after an
+    // edit to any crate of the workspace
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let provenance = provenance::provenance(Path::new(&dir));
+    let (revision, dirty) = provenance.as_ref().map(|p| (p.revision.as_str(), p.dirty)).unwrap_or_default();
+    println!("cargo:rustc-env=RUNNER_GIT_REVISION={}", revision);
+    println!("cargo:rustc-env=RUNNER_GIT_DIRTY={}", dirty);
+    for path in provenance.iter().flat_map(|p| &p.watched) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
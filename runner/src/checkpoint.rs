@@ -0,0 +1,100 @@
+use crate::config::invalid;
+use crate::manifest::CellRecord;
+use crate::output::Cell;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+// append-only record of finished grid cells: a header line describing the run,
+// then one line per cell with the seed its results were produced with
+pub struct Checkpoint {
+    file: File,
+    completed: HashMap<Cell, CellRecord>,
+}
+
+impl Checkpoint {
+    pub fn create(path: &Path, header: &Value) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", header)?;
+        file.sync_data()?;
+
+        Ok(Self {
+            file,
+            completed: HashMap::new(),
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<(Value, HashMap<Cell, CellRecord>)> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+
+        let header = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or_else(|| invalid(format!("{} has no header", path.display())))?;
+
+        // a torn last line from an interrupted write is simply not counted
+        let completed = lines
+            .filter_map(|line| {
+                let entry: Value = serde_json::from_str(line).ok()?;
+                let params = entry["params"]
+                    .as_array()?
+                    .iter()
+                    .map(|p| p.as_str().map(str::to_string))
+                    .collect::<Option<_>>()?;
+                let record = CellRecord {
+                    cell: Cell::new(entry["algorithm"].as_str()?, entry["workload"].as_str()?, params),
+                    seed: entry["seed"].as_u64()?,
+                    reps: entry["reps"].as_u64()? as usize,
+                    duration: entry["duration_secs"].as_f64()?,
+                };
+                Some((record.cell.clone(), record))
+            })
+            .collect();
+
+        Ok((header, completed))
+    }
+
+    // continues an existing checkpoint, rewriting it with only the given cells
+    pub fn resume(path: &Path, header: &Value, completed: HashMap<Cell, CellRecord>) -> io::Result<Self> {
+        let tmp = path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        writeln!(file, "{}", header)?;
+        for record in completed.values() {
+            writeln!(file, "{}", entry(record))?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+
+        Ok(Self {
+            file: OpenOptions::new().append(true).open(path)?,
+            completed,
+        })
+    }
+
+    // the record of a cell finished with the given seed, if any
+    pub fn completed(&self, cell: &Cell, seed: u64) -> Option<&CellRecord> {
+        self.completed.get(cell).filter(|record| record.seed == seed)
+    }
+
+    pub fn complete(&mut self, record: CellRecord) -> io::Result<()> {
+        writeln!(self.file, "{}", entry(&record))?;
+        self.file.sync_data()?;
+        self.completed.insert(record.cell.clone(), record);
+        Ok(())
+    }
+}
+
+fn entry(record: &CellRecord) -> Value {
+    json!({
+        "algorithm": record.cell.algorithm,
+        "workload": record.cell.workload,
+        "params": record.cell.params,
+        "seed": record.seed,
+        "reps": record.reps,
+        "duration_secs": record.duration,
+    })
+}
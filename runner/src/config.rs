@@ -0,0 +1,209 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    Csv, Jsonl, Both,
+}
+
+impl Format {
+    pub fn csv(&self) -> bool {
+        matches!(self, Format::Csv | Format::Both)
+    }
+
+    pub fn jsonl(&self) -> bool {
+        matches!(self, Format::Jsonl | Format::Both)
+    }
+}
+
+// which steps of a cost curve are recorded
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Sampling {
+    All, Final, Every(usize), Log(usize),
+}
+
+impl Sampling {
+    // the recorded steps of a curve of the given length, 0-based and increasing;
+    // the last step is always among them
+    pub fn steps(&self, len: usize) -> Vec<usize> {
+        if len == 0 {
+            return Vec::new();
+        }
+
+        let mut steps = match *self {
+            Sampling::All => (0..len).collect(),
+            Sampling::Final => Vec::new(),
+            Sampling::Every(k) => (k..=len).step_by(k).map(|s| s - 1).collect(),
+            Sampling::Log(per_decade) => {
+                let mut steps: Vec<usize> = Vec::new();
+                for i in 0.. {
+                    let step = 10f64.powf(i as f64 / per_decade as f64).round() as usize;
+                    if step > len {
+                        break;
+                    }
+                    if steps.last() != Some(&(step - 1)) {
+                        steps.push(step - 1);
+                    }
+                }
+                steps
+            }
+        };
+        if steps.last() != Some(&(len - 1)) {
+            steps.push(len - 1);
+        }
+        steps
+    }
+}
+
+impl fmt::Display for Sampling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sampling::All => write!(f, "all"),
+            Sampling::Final => write!(f, "final"),
+            Sampling::Every(k) => write!(f, "every:{}", k),
+            Sampling::Log(per_decade) => write!(f, "log:{}", per_decade),
+        }
+    }
+}
+
+pub fn parse_sampling(value: &str) -> io::Result<Sampling> {
+    let bad = || invalid(format!("invalid sampling: {} (expected all, final, every:K or log:P)", value));
+    let count = |n: &str| n.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(bad);
+
+    match value.split_once(':') {
+        None if value == "all" => Ok(Sampling::All),
+        None if value == "final" => Ok(Sampling::Final),
+        Some(("every", k)) => Ok(Sampling::Every(count(k)?)),
+        Some(("log", per_decade)) => Ok(Sampling::Log(count(per_decade)?)),
+        _ => Err(bad()),
+    }
+}
+
+fn parse_reps(value: &str) -> io::Result<usize> {
+    value.parse().ok().filter(|&n| n > 0).ok_or_else(|| invalid(format!("invalid number of reps: {}", value)))
+}
+
+// name=value with a positive value
+fn parse_override(value: &str) -> io::Result<(String, usize)> {
+    value
+        .split_once('=')
+        .and_then(|(name, n)| Some((name.to_string(), n.parse().ok().filter(|&n| n > 0)?)))
+        .ok_or_else(|| invalid(format!("invalid setting: {} (expected name=value)", value)))
+}
+
+// i/N with 1 <= i <= N
+pub fn parse_shard(value: &str) -> io::Result<(usize, usize)> {
+    value
+        .split_once('/')
+        .and_then(|(i, n)| Some((i.parse().ok()?, n.parse().ok()?)))
+        .filter(|&(i, n)| 1 <= i && i <= n)
+        .ok_or_else(|| invalid(format!("invalid shard: {} (expected i/N with 1 <= i <= N)", value)))
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub out: PathBuf,
+    pub format: Format,
+    pub seed: Option<u64>,
+    pub resume: bool,
+    pub sampling: Sampling,
+    // when set, each cell repeats until the relative 95% CI half-width of its
+    // final cost is at most this, within min_reps..=max_reps
+    pub target_ci: Option<f64>,
+    pub min_reps: usize,
+    pub max_reps: Option<usize>,
+    // replacements for the binary's own settings, such as reps or requests
+    pub overrides: Vec<(String, usize)>,
+    // when set to (i, N), only every N-th cell of the grid is run, starting
+    // with the i-th
+    pub shard: Option<(usize, usize)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            out: PathBuf::from("results"),
+            format: Format::Csv,
+            seed: None,
+            resume: false,
+            sampling: Sampling::Log(20),
+            target_ci: None,
+            min_reps: 10,
+            max_reps: None,
+            overrides: Vec::new(),
+            shard: None,
+        }
+    }
+}
+
+pub(crate) fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+impl Config {
+    pub fn from_args() -> io::Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> io::Result<Self> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| invalid(format!("missing value for {}", arg)));
+            match arg.as_str() {
+                "--out" => config.out = PathBuf::from(value()?),
+                "--format" => {
+                    config.format = match value()?.as_str() {
+                        "csv" => Format::Csv,
+                        "jsonl" => Format::Jsonl,
+                        "both" => Format::Both,
+                        other => return Err(invalid(format!("unknown format: {}", other))),
+                    }
+                }
+                "--seed" => {
+                    let seed = value()?;
+                    config.seed = Some(seed.parse().map_err(|_| invalid(format!("invalid seed: {}", seed)))?);
+                }
+                "--resume" => config.resume = true,
+                "--sample" => config.sampling = parse_sampling(&value()?)?,
+                "--target-ci" => {
+                    let target = value()?;
+                    config.target_ci = Some(target.parse().ok().filter(|&t: &f64| t > 0.0).ok_or_else(|| invalid(format!("invalid target: {}", target)))?);
+                }
+                "--min-reps" => config.min_reps = parse_reps(&value()?)?.max(2),
+                "--max-reps" => config.max_reps = Some(parse_reps(&value()?)?),
+                "--set" => config.overrides.push(parse_override(&value()?)?),
+                "--shard" => config.shard = Some(parse_shard(&value()?)?),
+                _ => return Err(invalid(format!("unknown argument: {}", arg))),
+            }
+        }
+
+        // the shards of a run only merge when they share the seed, and a drawn
+        // one would differ between them
+        if config.shard.is_some() && config.seed.is_none() {
+            return Err(invalid("--shard needs an explicit --seed shared by all shards".to_string()));
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> io::Result<Config> {
+        Config::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn shards_need_a_seed() {
+        let err = parse(&["--shard", "1/3"]).unwrap_err();
+        assert!(err.to_string().contains("--seed"), "{}", err);
+        let config = parse(&["--shard", "2/3", "--seed", "7"]).unwrap();
+        assert_eq!((config.shard, config.seed), (Some((2, 3)), Some(7)));
+        assert!(parse(&["--shard", "4/3", "--seed", "7"]).is_err());
+    }
+}
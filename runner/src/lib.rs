@@ -0,0 +1,330 @@
+pub mod checkpoint;
+pub mod config;
+pub mod manifest;
+pub mod merge;
+pub mod output;
+pub mod progress;
+pub mod provenance;
+pub mod snapshot;
+pub mod stats;
+
+use checkpoint::Checkpoint;
+use config::{invalid, Config, Sampling};
+use manifest::{CellRecord, Manifest, Package};
+use output::{Cell, ResultWriter};
+use progress::Progress;
+use serde_json::{json, Map, Value};
+use stats::{Curve, Samples};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+pub type Metrics = Vec<(&'static str, Samples)>;
+
+// the manifest lists every finished cell, so rewriting it after each one is
+// quadratic in the grid; between cells it is refreshed at most this often
+const MANIFEST_INTERVAL: Duration = Duration::from_secs(1);
+
+// FNV-1a over the fields, finalised with splitmix64
+fn hash_seed<'a>(base: u64, fields: impl Iterator<Item = &'a String>) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64 ^ base;
+    for field in fields {
+        for byte in field.bytes().chain([0xff]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    mix(hash)
+}
+
+// a cell's seed depends only on the base seed and its parameters, not on the
+// grid order
+fn cell_seed(base: u64, cell: &Cell) -> u64 {
+    hash_seed(base, [&cell.algorithm, &cell.workload].into_iter().chain(&cell.params))
+}
+
+// leaves the algorithm out, so that every algorithm of a comparison is
+// measured on the same requests
+fn workload_seed(base: u64, cell: &Cell) -> u64 {
+    hash_seed(base, [&cell.workload].into_iter().chain(&cell.params))
+}
+
+// the coins of a cell's algorithm, fresh in every repetition
+fn rep_seed(cell_seed: u64, rep: usize) -> u64 {
+    mix(cell_seed ^ (rep as u64).wrapping_mul(0x9e3779b97f4a7c15))
+}
+
+// the splitmix64 finaliser
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+// when a cell stops adding repetitions
+#[derive(Debug, Copy, Clone)]
+enum Reps {
+    Fixed(usize),
+    Adaptive { target: f64, min: usize, max: usize },
+}
+
+// what a cell's measurement gets from the runner
+pub struct Context<'a> {
+    seed: u64,
+    workload_seed: u64,
+    sampling: Sampling,
+    reps: Reps,
+    rep: usize,
+    progress: &'a mut Progress,
+}
+
+impl Context<'_> {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // a seed for the requests, the same for every algorithm of the workload
+    pub fn workload_seed(&self) -> u64 {
+        self.workload_seed
+    }
+
+    // a seed for the randomized algorithm of the current repetition
+    pub fn rep_seed(&self) -> u64 {
+        rep_seed(self.seed, self.rep)
+    }
+
+    // an empty curve of `len` steps recording only the configured sample of them
+    pub fn curve(&self, len: usize) -> Curve {
+        Curve::sampled(self.sampling.steps(len))
+    }
+
+    // whether to run another repetition, judged by the final cost of `curve`
+    pub fn next_rep(&mut self, curve: &Curve) -> bool {
+        let more = match self.reps {
+            Reps::Fixed(reps) => self.rep < reps,
+            Reps::Adaptive { target, min, max } => {
+                self.rep < min || (self.rep < max && curve.relative_half_width() > target)
+            }
+        };
+        if more {
+            self.rep += 1;
+        }
+        more
+    }
+
+    // reports that another repetition of `steps` steps has finished
+    pub fn advance(&mut self, steps: usize) {
+        self.progress.advance(steps);
+    }
+}
+
+pub struct Runner {
+    seed: u64,
+    sampling: Sampling,
+    reps: Reps,
+    shard: Option<(usize, usize)>,
+    // position in the grid of the next cell passed to run
+    next_cell: usize,
+    out: PathBuf,
+    writer: ResultWriter,
+    checkpoint: Checkpoint,
+    progress: Progress,
+    manifest: Manifest,
+    manifest_written: Instant,
+    started: Instant,
+}
+
+impl Runner {
+    pub fn new(package: Package, problem: &str, param_names: &[&str], settings: &[(&str, usize)]) -> io::Result<Self> {
+        Self::with_config(Config::from_args()?, package, problem, param_names, settings)
+    }
+
+    pub fn with_config(config: Config, package: Package, problem: &str, param_names: &[&str], settings: &[(&str, usize)]) -> io::Result<Self> {
+        fs::create_dir_all(&config.out)?;
+        let path = config.out.join("checkpoint.jsonl");
+        let mut settings = settings
+            .iter()
+            .map(|(name, value)| (name.to_string(), json!(value)))
+            .collect::<Map<String, Value>>();
+        for (name, value) in &config.overrides {
+            if !settings.contains_key(name) {
+                let known = settings.keys().cloned().collect::<Vec<_>>().join(", ");
+                return Err(invalid(format!("unknown setting: {} (expected one of {})", name, known)));
+            }
+            settings.insert(name.clone(), json!(value));
+        }
+        settings.insert("sample".to_string(), json!(config.sampling.to_string()));
+        if let Some((i, n)) = config.shard {
+            settings.insert("shard".to_string(), json!(format!("{}/{}", i, n)));
+        }
+
+        let fixed = settings.get("reps").and_then(|r| r.as_u64()).unwrap_or(1) as usize;
+        let reps = match config.target_ci {
+            None => Reps::Fixed(fixed),
+            Some(target) => {
+                let max = config.max_reps.unwrap_or(10 * fixed).max(config.min_reps);
+                settings.insert("target_ci".to_string(), json!(target));
+                settings.insert("min_reps".to_string(), json!(config.min_reps));
+                settings.insert("max_reps".to_string(), json!(max));
+                Reps::Adaptive { target, min: config.min_reps, max }
+            }
+        };
+        let header = |seed: u64| {
+            json!({
+                "problem": problem,
+                "params": param_names,
+                "settings": settings,
+                "seed": seed,
+            })
+        };
+
+        let (seed, writer, checkpoint) = if config.resume && path.exists() {
+            let (found, completed) = Checkpoint::load(&path)?;
+            let seed = found["seed"].as_u64().unwrap_or_default();
+            if found != header(config.seed.unwrap_or(seed)) {
+                return Err(invalid(format!("{} was written by a different configuration, cannot resume", path.display())));
+            }
+
+            let cells = completed.keys().cloned().collect::<HashSet<_>>();
+            let (writer, present) = ResultWriter::resume(&config, problem, param_names, &cells)?;
+            let completed = completed.into_iter().filter(|(cell, _)| present.contains(cell)).collect();
+            (seed, writer, Checkpoint::resume(&path, &found, completed)?)
+        } else {
+            let seed = config.seed.unwrap_or_else(rand::random);
+            let writer = ResultWriter::create(&config, problem, param_names)?;
+            (seed, writer, Checkpoint::create(&path, &header(seed))?)
+        };
+
+        let mut outputs = Vec::new();
+        if config.format.csv() {
+            outputs.push("results.csv".to_string());
+        }
+        if config.format.jsonl() {
+            outputs.push("results.jsonl".to_string());
+        }
+        let manifest = Manifest::new(package, problem, param_names, settings, seed, outputs);
+        manifest.write(&config.out)?;
+
+        Ok(Self {
+            seed,
+            sampling: config.sampling,
+            reps,
+            shard: config.shard,
+            next_cell: 0,
+            out: config.out,
+            writer,
+            checkpoint,
+            progress: Progress::hidden(),
+            manifest,
+            manifest_written: Instant::now(),
+            started: Instant::now(),
+        })
+    }
+
+    // announces the size of the grid so progress can be reported against it
+    pub fn plan(&mut self, cells: usize, reps: usize, steps: usize) {
+        let cells = match self.shard {
+            Some((i, n)) => (cells + n - i) / n,
+            None => cells,
+        };
+        self.progress = Progress::new(cells, reps, steps);
+    }
+
+    // the value of one of the settings the runner was created with, after
+    // any --set override
+    pub fn setting(&self, name: &str) -> usize {
+        self.manifest.settings[name].as_u64().unwrap_or_else(|| panic!("no setting {}", name)) as usize
+    }
+
+    pub fn seed(&self, cell: &Cell) -> u64 {
+        cell_seed(self.seed, cell)
+    }
+
+    // measures a cell unless it belongs to another shard or a resumed
+    // checkpoint already holds its results
+    pub fn run<F>(&mut self, cell: Cell, measure: F) -> io::Result<()>
+    where F: FnOnce(&mut Context) -> Metrics
+    {
+        let position = self.next_cell;
+        self.next_cell += 1;
+        if let Some((i, n)) = self.shard
+            && position % n != i - 1
+        {
+            return Ok(());
+        }
+
+        let seed = self.seed(&cell);
+        if let Some(record) = self.checkpoint.completed(&cell, seed) {
+            self.manifest.cells.push(record.clone());
+            self.progress.skip_cell();
+            return Ok(());
+        }
+
+        self.progress.start_cell(cell.to_string());
+        let started = Instant::now();
+        let mut context = Context {
+            seed,
+            workload_seed: workload_seed(self.seed, &cell),
+            sampling: self.sampling,
+            reps: self.reps,
+            rep: 0,
+            progress: &mut self.progress,
+        };
+        let metrics = measure(&mut context);
+        let reps = context.rep;
+        for (metric, curve) in metrics {
+            self.writer.write(&cell, metric, &curve)?;
+        }
+        self.writer.sync()?;
+
+        let record = CellRecord { cell, seed, reps, duration: started.elapsed().as_secs_f64() };
+        self.checkpoint.complete(record.clone())?;
+        self.manifest.cells.push(record);
+        if self.manifest_written.elapsed() >= MANIFEST_INTERVAL {
+            self.manifest.write(&self.out)?;
+            self.manifest_written = Instant::now();
+        }
+        self.progress.finish_cell();
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.progress.finish();
+        self.writer.flush()?;
+
+        self.manifest.finished = Some(manifest::timestamp(SystemTime::now()));
+        self.manifest.duration = self.started.elapsed().as_secs_f64();
+        self.manifest.write(&self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(algorithm: &str, workload: &str, elems: &str) -> Cell {
+        Cell::new(algorithm, workload, vec![elems.to_string()])
+    }
+
+    #[test]
+    fn workloads_share_their_seed_across_algorithms() {
+        let (mtf, ts) = (cell("MTF", "Uniform", "100"), cell("TS", "Uniform", "100"));
+        assert_eq!(workload_seed(7, &mtf), workload_seed(7, &ts));
+        assert_ne!(cell_seed(7, &mtf), cell_seed(7, &ts));
+        assert_ne!(workload_seed(7, &mtf), workload_seed(7, &cell("MTF", "Harmonic", "100")));
+        assert_ne!(workload_seed(7, &mtf), workload_seed(7, &cell("MTF", "Uniform", "200")));
+        assert_ne!(workload_seed(7, &mtf), workload_seed(8, &mtf));
+    }
+
+    #[test]
+    fn coins_differ_between_algorithms_of_a_workload() {
+        let (bit, comb) = (cell("BIT", "Uniform", "100"), cell("COMB", "Uniform", "100"));
+        assert_eq!(workload_seed(7, &bit), workload_seed(7, &comb));
+        assert_ne!(rep_seed(cell_seed(7, &bit), 1), rep_seed(cell_seed(7, &comb), 1));
+        assert_ne!(rep_seed(cell_seed(7, &bit), 1), rep_seed(cell_seed(7, &bit), 2));
+    }
+}
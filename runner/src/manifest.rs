@@ -0,0 +1,200 @@
+use crate::config::invalid;
+use crate::output::{Cell, Results};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MANIFEST: &str = "manifest.json";
+
+// name and version of the crate producing a run
+#[derive(Debug, Clone, Copy)]
+pub struct Package {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+#[macro_export]
+macro_rules! package {
+    () => {
+        $crate::manifest::Package {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellRecord {
+    pub cell: Cell,
+    pub seed: u64,
+    pub reps: usize,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub problem: String,
+    pub param_names: Vec<String>,
+    pub settings: Map<String, Value>,
+    pub seed: u64,
+    pub package: (String, String),
+    pub runner_version: String,
+    pub rustc: String,
+    pub git_revision: Option<String>,
+    pub git_dirty: bool,
+    pub args: Vec<String>,
+    // the command lines of the shards merged into this run, in shard order,
+    // while args holds the merge's own; empty for a run made in one go
+    pub shards: Vec<Vec<String>>,
+    pub outputs: Vec<String>,
+    pub started: String,
+    pub finished: Option<String>,
+    pub duration: f64,
+    pub cells: Vec<CellRecord>,
+}
+
+// RFC 3339 UTC timestamp, using the days-to-civil conversion from H. Hinnant
+pub fn timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default() as i64;
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+impl Manifest {
+    pub fn new(package: Package, problem: &str, param_names: &[&str], settings: Map<String, Value>, seed: u64, outputs: Vec<String>) -> Self {
+        // captured when the runner was built
+        let git_revision = Some(env!("RUNNER_GIT_REVISION")).filter(|r| !r.is_empty()).map(str::to_string);
+        let git_dirty = env!("RUNNER_GIT_DIRTY") == "true";
+
+        Self {
+            problem: problem.to_string(),
+            param_names: param_names.iter().map(|p| p.to_string()).collect(),
+            settings,
+            seed,
+            package: (package.name.to_string(), package.version.to_string()),
+            runner_version: env!("CARGO_PKG_VERSION").to_string(),
+            rustc: env!("RUNNER_RUSTC_VERSION").to_string(),
+            git_revision,
+            git_dirty,
+            args: std::env::args().collect(),
+            shards: Vec::new(),
+            outputs,
+            started: timestamp(SystemTime::now()),
+            finished: None,
+            duration: 0.0,
+            cells: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "problem": self.problem,
+            "params": self.param_names,
+            "settings": self.settings,
+            "seed": self.seed,
+            "package": { "name": self.package.0, "version": self.package.1 },
+            "runner_version": self.runner_version,
+            "rustc": self.rustc,
+            "git": { "revision": self.git_revision, "dirty": self.git_dirty },
+            "args": self.args,
+            "shards": self.shards,
+            "outputs": self.outputs,
+            "started": self.started,
+            "finished": self.finished,
+            "duration_secs": self.duration,
+            "cells": self.cells.iter().map(|record| json!({
+                "algorithm": record.cell.algorithm,
+                "workload": record.cell.workload,
+                "params": record.cell.params,
+                "seed": record.seed,
+                "reps": record.reps,
+                "duration_secs": record.duration,
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let strings = |v: &Value| -> Option<Vec<String>> {
+            v.as_array()?.iter().map(|s| s.as_str().map(str::to_string)).collect()
+        };
+
+        let cells = value["cells"]
+            .as_array()?
+            .iter()
+            .map(|c| {
+                Some(CellRecord {
+                    cell: Cell::new(c["algorithm"].as_str()?, c["workload"].as_str()?, strings(&c["params"])?),
+                    seed: c["seed"].as_u64()?,
+                    reps: c["reps"].as_u64()? as usize,
+                    duration: c["duration_secs"].as_f64()?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            problem: value["problem"].as_str()?.to_string(),
+            param_names: strings(&value["params"])?,
+            settings: value["settings"].as_object()?.clone(),
+            seed: value["seed"].as_u64()?,
+            package: (value["package"]["name"].as_str()?.to_string(), value["package"]["version"].as_str()?.to_string()),
+            runner_version: value["runner_version"].as_str()?.to_string(),
+            rustc: value["rustc"].as_str()?.to_string(),
+            git_revision: value["git"]["revision"].as_str().map(str::to_string),
+            git_dirty: value["git"]["dirty"].as_bool()?,
+            args: strings(&value["args"])?,
+            shards: value["shards"].as_array()?.iter().map(strings).collect::<Option<_>>()?,
+            outputs: strings(&value["outputs"])?,
+            started: value["started"].as_str()?.to_string(),
+            finished: value["finished"].as_str().map(str::to_string),
+            duration: value["duration_secs"].as_f64()?,
+            cells,
+        })
+    }
+
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        let tmp = dir.join(format!("{}.tmp", MANIFEST));
+        fs::write(&tmp, serde_json::to_string_pretty(&self.to_json())? + "\n")?;
+        fs::rename(tmp, dir.join(MANIFEST))
+    }
+
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join(MANIFEST);
+        let value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        Self::from_json(&value).ok_or_else(|| invalid(format!("{} is not a valid manifest", path.display())))
+    }
+
+    // checks that a results file holds exactly the cells this manifest says were produced
+    pub fn validate(&self, results: &Results) -> Result<(), String> {
+        if results.param_names != self.param_names {
+            return Err(format!("results have parameters {:?}, manifest has {:?}", results.param_names, self.param_names));
+        }
+        if let Some(row) = results.rows.iter().find(|row| row.problem != self.problem) {
+            return Err(format!("results contain problem {}, manifest is for {}", row.problem, self.problem));
+        }
+
+        let expected = self.cells.iter().map(|r| &r.cell).collect::<HashSet<_>>();
+        let found = results.rows.iter().map(|r| &r.cell).collect::<HashSet<_>>();
+        if let Some(cell) = expected.difference(&found).next() {
+            return Err(format!("cell ({}) is in the manifest but has no results", cell));
+        }
+        if let Some(cell) = found.difference(&expected).next() {
+            return Err(format!("cell ({}) has results but is not in the manifest", cell));
+        }
+
+        Ok(())
+    }
+}
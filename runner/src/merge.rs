@@ -0,0 +1,139 @@
+use crate::config::{invalid, parse_shard};
+use crate::manifest::{CellRecord, Manifest};
+use crate::output::{csv_cell, jsonl_cell, Cell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// the lines of an output file, grouped by cell
+type Rows = HashMap<Cell, Vec<String>>;
+
+// the header and rows of one output file of a shard
+fn read_rows(dir: &Path, output: &str, manifest: &Manifest) -> io::Result<(Option<String>, Rows)> {
+    let path = dir.join(output);
+    let contents = fs::read_to_string(&path)?;
+    let mut lines = contents.lines();
+    let header = output.ends_with(".csv").then(|| lines.next().unwrap_or_default().to_string());
+
+    let mut rows = Rows::new();
+    for (i, line) in lines.enumerate() {
+        let cell = if header.is_some() { csv_cell(&manifest.param_names, line) } else { jsonl_cell(&manifest.param_names, line) };
+        let cell = cell.ok_or_else(|| invalid(format!("{}:{}: malformed row", path.display(), i + 1 + header.is_some() as usize)))?;
+        rows.entry(cell).or_default().push(line.to_string());
+    }
+
+    let expected = manifest.cells.iter().map(|r| &r.cell).collect::<HashSet<_>>();
+    if let Some(cell) = expected.iter().find(|cell| !rows.contains_key(cell)) {
+        return Err(invalid(format!("{}: cell ({}) is in the manifest but has no results", path.display(), cell)));
+    }
+    if let Some(cell) = rows.keys().find(|cell| !expected.contains(cell)) {
+        return Err(invalid(format!("{}: cell ({}) has results but is not in the manifest", path.display(), cell)));
+    }
+
+    Ok((header, rows))
+}
+
+// combines the outputs of every shard of a run made with --shard i/N into
+// `out`, checking that together they cover the grid exactly once; the cells
+// come out in the order an unsharded run writes them
+pub fn merge(shards: &[PathBuf], out: &Path) -> io::Result<Manifest> {
+    let manifests = shards.iter().map(|dir| Manifest::load(dir)).collect::<io::Result<Vec<_>>>()?;
+    let Some(first) = manifests.first() else {
+        return Err(invalid("no shards to merge".to_string()));
+    };
+
+    let shard_of = |dir: &Path, manifest: &Manifest| {
+        let shard = manifest.settings.get("shard").and_then(|s| s.as_str());
+        shard
+            .ok_or_else(|| invalid(format!("{} was not run with --shard", dir.display())))
+            .and_then(parse_shard)
+    };
+    let (_, count) = shard_of(&shards[0], first)?;
+    let mut slots = vec![None; count];
+    let unsharded = |manifest: &Manifest| {
+        let mut settings = manifest.settings.clone();
+        settings.remove("shard");
+        settings
+    };
+
+    for (dir, manifest) in shards.iter().zip(&manifests) {
+        let (i, n) = shard_of(dir, manifest)?;
+        let same = manifest.problem == first.problem
+            && manifest.param_names == first.param_names
+            && manifest.seed == first.seed
+            && manifest.package == first.package
+            && manifest.outputs == first.outputs
+            && unsharded(manifest) == unsharded(first)
+            && n == count;
+        if !same {
+            return Err(invalid(format!("{} is from a different run than {}", dir.display(), shards[0].display())));
+        }
+        if let Some((other, _)) = slots[i - 1].replace((dir, manifest)) {
+            return Err(invalid(format!("{} and {} are both shard {}/{}", other.display(), dir.display(), i, n)));
+        }
+        if manifest.finished.is_none() {
+            return Err(invalid(format!("the run in {} has not finished, so some of its cells are missing", dir.display())));
+        }
+    }
+    if let Some(i) = slots.iter().position(|slot| slot.is_none()) {
+        return Err(invalid(format!("shard {}/{} is missing", i + 1, count)));
+    }
+
+    // shard i holds the cells at grid positions i - 1, i - 1 + N, ...
+    let ordered = slots.into_iter().map(|slot| slot.unwrap().1).collect::<Vec<_>>();
+    let total = manifests.iter().map(|m| m.cells.len()).sum::<usize>();
+    for (i, manifest) in ordered.iter().enumerate() {
+        let expected = (total + count - i - 1) / count;
+        if manifest.cells.len() != expected {
+            return Err(invalid(format!(
+                "shard {}/{} has {} cells where a grid of {} needs {}, so the shards ran different grids",
+                i + 1, count, manifest.cells.len(), total, expected
+            )));
+        }
+    }
+    let cells = (0..total).map(|k| ordered[k % count].cells[k / count].clone()).collect::<Vec<CellRecord>>();
+    let mut seen = HashSet::new();
+    if let Some(record) = cells.iter().find(|record| !seen.insert(&record.cell)) {
+        return Err(invalid(format!("cell ({}) is in more than one shard", record.cell)));
+    }
+
+    fs::create_dir_all(out)?;
+    for output in &first.outputs {
+        let read = shards
+            .iter()
+            .zip(&manifests)
+            .map(|(dir, manifest)| read_rows(dir, output, manifest))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut rows = HashMap::new();
+        let mut merged = String::new();
+        for (header, shard_rows) in read {
+            if let Some(header) = header
+                && merged.is_empty()
+            {
+                merged = header + "\n";
+            }
+            rows.extend(shard_rows);
+        }
+        for record in &cells {
+            for line in &rows[&record.cell] {
+                merged += line;
+                merged.push('\n');
+            }
+        }
+        fs::write(out.join(output), merged)?;
+    }
+
+    let mut manifest = first.clone();
+    manifest.settings = unsharded(first);
+    manifest.args = std::env::args().collect();
+    manifest.shards = ordered.iter().map(|m| m.args.clone()).collect();
+    manifest.started = manifests.iter().map(|m| m.started.clone()).min().unwrap_or_default();
+    manifest.finished = manifests.iter().filter_map(|m| m.finished.clone()).max();
+    // shards run side by side, so the run took as long as the slowest
+    manifest.duration = manifests.iter().map(|m| m.duration).fold(0.0, f64::max);
+    manifest.cells = cells;
+    manifest.write(out)?;
+
+    Ok(manifest)
+}
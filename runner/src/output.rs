@@ -0,0 +1,363 @@
+use crate::config::{invalid, Config};
+use crate::stats::Summary;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub const SUMMARY_COLUMNS: [&str; 9] = [
+    "step", "reps", "mean", "variance", "std_err", "ci_low", "ci_high", "median", "p95",
+];
+
+// one point of an experiment grid
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub algorithm: String,
+    pub workload: String,
+    pub params: Vec<String>,
+}
+
+impl Cell {
+    pub fn new<A: fmt::Display, W: fmt::Display>(algorithm: A, workload: W, params: Vec<String>) -> Self {
+        Self {
+            algorithm: algorithm.to_string(),
+            workload: workload.to_string(),
+            params,
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.algorithm, self.workload)?;
+        for param in &self.params {
+            write!(f, ", {}", param)?;
+        }
+        Ok(())
+    }
+}
+
+fn json_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn json_value(value: &str) -> Value {
+    if let Ok(number) = value.parse::<i64>() {
+        return Value::from(number);
+    }
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Value::from(number),
+        _ => Value::from(value),
+    }
+}
+
+// the cell a row of results.csv belongs to
+pub(crate) fn csv_cell<S: AsRef<str>>(param_names: &[S], line: &str) -> Option<Cell> {
+    let fields = line.split(',').collect::<Vec<_>>();
+    if fields.len() < 3 + param_names.len() {
+        return None;
+    }
+    let params = fields[3..(3 + param_names.len())].iter().map(|p| p.to_string()).collect();
+    Some(Cell::new(fields[1], fields[2], params))
+}
+
+// the cell a row of results.jsonl belongs to
+pub(crate) fn jsonl_cell<S: AsRef<str>>(param_names: &[S], line: &str) -> Option<Cell> {
+    let row = serde_json::from_str::<Map<String, Value>>(line).ok()?;
+    let params = param_names.iter().map(|p| row.get(p.as_ref()).map(json_string)).collect::<Option<_>>()?;
+    Some(Cell::new(json_string(row.get("algorithm")?), json_string(row.get("workload")?), params))
+}
+
+// long-format output: one row per (cell, metric, step)
+pub struct ResultWriter {
+    problem: String,
+    param_names: Vec<String>,
+    csv: Option<BufWriter<File>>,
+    jsonl: Option<BufWriter<File>>,
+}
+
+impl ResultWriter {
+    pub fn create(config: &Config, problem: &str, param_names: &[&str]) -> io::Result<Self> {
+        Ok(Self::open(config, problem, param_names, None)?.0)
+    }
+
+    // reopens existing outputs for appending, keeping only the rows of cells in `completed`;
+    // returns the cells whose rows were found in every output
+    pub fn resume(config: &Config, problem: &str, param_names: &[&str], completed: &HashSet<Cell>) -> io::Result<(Self, HashSet<Cell>)> {
+        Self::open(config, problem, param_names, Some(completed))
+    }
+
+    fn open(config: &Config, problem: &str, param_names: &[&str], completed: Option<&HashSet<Cell>>) -> io::Result<(Self, HashSet<Cell>)> {
+        fs::create_dir_all(&config.out)?;
+        let mut present = completed.cloned().unwrap_or_default();
+
+        let csv = if config.format.csv() {
+            let header = ["problem", "algorithm", "workload"]
+                .iter()
+                .chain(param_names)
+                .chain(&["metric"])
+                .chain(&SUMMARY_COLUMNS)
+                .copied()
+                .collect::<Vec<_>>()
+                .join(",");
+            let (writer, found) = open_lines(&config.out.join("results.csv"), Some(&header), completed, |line| csv_cell(param_names, line))?;
+            present.retain(|cell| found.contains(cell));
+            Some(writer)
+        } else {
+            None
+        };
+
+        let jsonl = if config.format.jsonl() {
+            let (writer, found) = open_lines(&config.out.join("results.jsonl"), None, completed, |line| jsonl_cell(param_names, line))?;
+            present.retain(|cell| found.contains(cell));
+            Some(writer)
+        } else {
+            None
+        };
+
+        let writer = Self {
+            problem: problem.to_string(),
+            param_names: param_names.iter().map(|p| p.to_string()).collect(),
+            csv,
+            jsonl,
+        };
+
+        Ok((writer, present))
+    }
+
+    pub fn write(&mut self, cell: &Cell, metric: &str, curve: &[(usize, Summary)]) -> io::Result<()> {
+        assert_eq!(cell.params.len(), self.param_names.len());
+
+        if let Some(writer) = self.csv.as_mut() {
+            let prefix = [&self.problem, &cell.algorithm, &cell.workload]
+                .into_iter()
+                .chain(&cell.params)
+                .map(|v| v.as_str())
+                .chain([metric])
+                .collect::<Vec<_>>()
+                .join(",");
+            for (step, s) in curve {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    prefix, step, s.reps, s.mean, s.variance, s.std_err, s.ci_low, s.ci_high, s.median, s.p95
+                )?;
+            }
+        }
+
+        if let Some(writer) = self.jsonl.as_mut() {
+            for (step, s) in curve {
+                let mut row = Map::new();
+                row.insert("problem".into(), Value::from(self.problem.as_str()));
+                row.insert("algorithm".into(), Value::from(cell.algorithm.as_str()));
+                row.insert("workload".into(), Value::from(cell.workload.as_str()));
+                for (name, value) in self.param_names.iter().zip(&cell.params) {
+                    row.insert(name.clone(), json_value(value));
+                }
+                row.insert("metric".into(), Value::from(metric));
+                row.insert("step".into(), Value::from(*step));
+                row.insert("reps".into(), Value::from(s.reps));
+                let values = [s.mean, s.variance, s.std_err, s.ci_low, s.ci_high, s.median, s.p95];
+                for (name, value) in SUMMARY_COLUMNS[2..].iter().zip(values) {
+                    row.insert(name.to_string(), Value::from(value));
+                }
+                writeln!(writer, "{}", Value::Object(row))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(writer) = self.csv.as_mut() {
+            writer.flush()?;
+        }
+        if let Some(writer) = self.jsonl.as_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    // flushes the rows and waits until they are on disk, so that a checkpoint
+    // written afterwards never covers rows lost in a crash
+    pub fn sync(&mut self) -> io::Result<()> {
+        for writer in [self.csv.as_mut(), self.jsonl.as_mut()].into_iter().flatten() {
+            writer.flush()?;
+            writer.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+fn open_lines<F>(path: &Path, header: Option<&str>, completed: Option<&HashSet<Cell>>, cell_of: F) -> io::Result<(BufWriter<File>, HashSet<Cell>)>
+where F: Fn(&str) -> Option<Cell>
+{
+    let mut found = HashSet::new();
+
+    let kept = match completed {
+        Some(completed) if path.exists() => {
+            let contents = fs::read_to_string(path)?;
+            let mut lines = contents.lines();
+            if let Some(header) = header
+                && lines.next() != Some(header)
+            {
+                return Err(invalid(format!("{} has a different header, cannot resume", path.display())));
+            }
+            lines
+                .filter(|line| match cell_of(line) {
+                    Some(cell) if completed.contains(&cell) => {
+                        found.insert(cell);
+                        true
+                    }
+                    _ => false,
+                })
+                .map(|line| line.to_string())
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    // rewrite through a temporary file so an interruption here cannot lose finished rows
+    let tmp = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    if let Some(header) = header {
+        writeln!(writer, "{}", header)?;
+    }
+    for line in kept {
+        writeln!(writer, "{}", line)?;
+    }
+    writer.into_inner()?.sync_all()?;
+    fs::rename(&tmp, path)?;
+
+    let file = OpenOptions::new().append(true).open(path)?;
+    Ok((BufWriter::new(file), found))
+}
+
+// one parsed row of a long-format results file
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub problem: String,
+    pub cell: Cell,
+    pub metric: String,
+    pub step: usize,
+    pub summary: Summary,
+}
+
+pub struct Results {
+    pub param_names: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+impl Results {
+    pub fn value<'a>(&'a self, row: &'a Row, column: &str) -> Option<&'a str> {
+        match column {
+            "problem" => Some(&row.problem),
+            "algorithm" => Some(&row.cell.algorithm),
+            "workload" => Some(&row.cell.workload),
+            "metric" => Some(&row.metric),
+            _ => self.param_names
+                .iter()
+                .position(|name| name == column)
+                .map(|i| row.cell.params[i].as_str()),
+        }
+    }
+}
+
+pub fn read_results(path: &Path) -> io::Result<Results> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = contents.lines();
+    let bad = |line: usize| invalid(format!("{}:{}: malformed row", path.display(), line));
+
+    let header = lines.next().ok_or_else(|| bad(1))?.split(',').collect::<Vec<_>>();
+    let metric = header.iter().position(|&c| c == "metric").ok_or_else(|| bad(1))?;
+    if metric < 3 || header[..3] != ["problem", "algorithm", "workload"] || header[(metric + 1)..] != SUMMARY_COLUMNS {
+        return Err(bad(1));
+    }
+    let param_names = header[3..metric].iter().map(|p| p.to_string()).collect();
+
+    let rows = lines
+        .enumerate()
+        .map(|(i, line)| {
+            let fields = line.split(',').collect::<Vec<_>>();
+            if fields.len() != header.len() {
+                return Err(bad(i + 2));
+            }
+            let number = |j: usize| fields[metric + 1 + j].parse::<f64>().map_err(|_| bad(i + 2));
+            Ok(Row {
+                problem: fields[0].to_string(),
+                cell: Cell::new(fields[1], fields[2], fields[3..metric].iter().map(|p| p.to_string()).collect()),
+                metric: fields[metric].to_string(),
+                step: fields[metric + 1].parse().map_err(|_| bad(i + 2))?,
+                summary: Summary {
+                    reps: fields[metric + 2].parse().map_err(|_| bad(i + 2))?,
+                    mean: number(2)?,
+                    variance: number(3)?,
+                    std_err: number(4)?,
+                    ci_low: number(5)?,
+                    ci_high: number(6)?,
+                    median: number(7)?,
+                    p95: number(8)?,
+                },
+            })
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok(Results { param_names, rows })
+}
+
+// the same rows from a results.jsonl, whose parameters are the fields between
+// workload and metric
+pub fn read_results_jsonl(path: &Path) -> io::Result<Results> {
+    let contents = fs::read_to_string(path)?;
+    let bad = |line: usize| invalid(format!("{}:{}: malformed row", path.display(), line));
+
+    let mut param_names = None;
+    let rows = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let row = serde_json::from_str::<Map<String, Value>>(line).map_err(|_| bad(i + 1))?;
+            let keys = row.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+            let metric = keys.iter().position(|&k| k == "metric").ok_or_else(|| bad(i + 1))?;
+            if metric < 3 || keys[..3] != ["problem", "algorithm", "workload"] || keys[(metric + 1)..] != SUMMARY_COLUMNS {
+                return Err(bad(i + 1));
+            }
+            let names = param_names.get_or_insert_with(|| keys[3..metric].iter().map(|k| k.to_string()).collect::<Vec<_>>());
+            if keys[3..metric] != *names {
+                return Err(bad(i + 1));
+            }
+
+            let string = |key: &str| json_string(&row[key]);
+            // serde_json writes non-finite numbers as null
+            let number = |key: &str| match &row[key] {
+                Value::Null => Ok(f64::NAN),
+                value => value.as_f64().ok_or_else(|| bad(i + 1)),
+            };
+            let count = |key: &str| row[key].as_u64().map(|n| n as usize).ok_or_else(|| bad(i + 1));
+            Ok(Row {
+                problem: string("problem"),
+                cell: Cell::new(string("algorithm"), string("workload"), names.iter().map(|name| string(name)).collect()),
+                metric: string("metric"),
+                step: count("step")?,
+                summary: Summary {
+                    reps: count("reps")?,
+                    mean: number("mean")?,
+                    variance: number("variance")?,
+                    std_err: number("std_err")?,
+                    ci_low: number("ci_low")?,
+                    ci_high: number("ci_high")?,
+                    median: number("median")?,
+                    p95: number("p95")?,
+                },
+            })
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok(Results { param_names: param_names.unwrap_or_default(), rows })
+}
@@ -0,0 +1,127 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const REDRAW: Duration = Duration::from_millis(200);
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    match rate {
+        r if r >= 1e9 => format!("{:.1}G", r / 1e9),
+        r if r >= 1e6 => format!("{:.1}M", r / 1e6),
+        r if r >= 1e3 => format!("{:.1}k", r / 1e3),
+        r => format!("{:.0}", r),
+    }
+}
+
+// tracks work done across a grid of cells x reps x steps; redraws a single
+// status line on a terminal and logs one line per cell otherwise
+pub struct Progress {
+    cells: usize,
+    steps_per_cell: u64,
+    cell: usize,
+    label: String,
+    total: u64,
+    done: u64,
+    cell_done: u64,
+    skipped: u64,
+    start: Instant,
+    last_draw: Option<Instant>,
+    tty: bool,
+}
+
+impl Progress {
+    pub fn new(cells: usize, reps: usize, steps: usize) -> Self {
+        let steps_per_cell = (reps * steps) as u64;
+        Self {
+            cells,
+            steps_per_cell,
+            cell: 0,
+            label: String::new(),
+            total: cells as u64 * steps_per_cell,
+            done: 0,
+            cell_done: 0,
+            skipped: 0,
+            start: Instant::now(),
+            last_draw: None,
+            tty: io::stdout().is_terminal(),
+        }
+    }
+
+    pub fn hidden() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    pub fn start_cell(&mut self, label: String) {
+        self.cell += 1;
+        self.label = label;
+        self.cell_done = 0;
+        self.draw(false);
+    }
+
+    pub fn skip_cell(&mut self) {
+        self.cell += 1;
+        self.done += self.steps_per_cell;
+        self.skipped += self.steps_per_cell;
+    }
+
+    pub fn advance(&mut self, steps: usize) {
+        self.done += steps as u64;
+        self.cell_done += steps as u64;
+        self.draw(false);
+    }
+
+    pub fn finish_cell(&mut self) {
+        // a cell with adaptive repetitions may do more or less than planned
+        self.total = (self.total + self.cell_done).saturating_sub(self.steps_per_cell);
+        self.draw(!self.tty);
+    }
+
+    pub fn finish(&mut self) {
+        if self.tty && self.last_draw.is_some() {
+            self.draw(true);
+            println!();
+        }
+    }
+
+    fn status(&self) -> String {
+        let elapsed = self.start.elapsed();
+        let measured = self.done - self.skipped;
+        let rate = measured as f64 / elapsed.as_secs_f64().max(1e-9);
+
+        let mut status = format!("[{}/{}] {}", self.cell, self.cells, self.label);
+        if self.total > 0 {
+            status += &format!(": {:.1}%", 100.0 * self.done as f64 / self.total as f64);
+        }
+        status += &format!(", {} steps/s, elapsed {}", format_rate(rate), format_duration(elapsed));
+        if self.total > 0 && measured > 0 {
+            let remaining = self.total.saturating_sub(self.done) as f64 / rate;
+            status += &format!(", ETA {}", format_duration(Duration::from_secs_f64(remaining)));
+        }
+        status
+    }
+
+    fn draw(&mut self, force: bool) {
+        if self.cells == 0 {
+            return;
+        }
+
+        if self.tty {
+            if !force && self.last_draw.is_some_and(|t| t.elapsed() < REDRAW) {
+                return;
+            }
+            print!("\r\x1b[2K{}", self.status());
+            let _ = io::stdout().flush();
+            self.last_draw = Some(Instant::now());
+        } else if force {
+            println!("{}", self.status());
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// the git state of a checkout, computed by the build script so that every
+// binary records the sources it was built from
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    pub revision: String,
+    // whether a tracked file differs from the revision, staged or not
+    pub dirty: bool,
+    // the files whose changes make the build script run again: every tracked
+    // file, and the git files a commit or a staged change rewrites
+    pub watched: Vec<PathBuf>,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok().map(|s| s.trim().to_string())
+}
+
+// None outside a git checkout
+pub fn provenance(dir: &Path) -> Option<Provenance> {
+    let revision = git(dir, &["rev-parse", "HEAD"])?;
+    let dirty = git(dir, &["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+
+    let top = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let mut watched = git(dir, &["ls-files"])?.lines().map(|file| top.join(file)).collect::<Vec<_>>();
+    let git_dir = PathBuf::from(git(dir, &["rev-parse", "--absolute-git-dir"])?);
+    watched.push(git_dir.join("HEAD"));
+    watched.push(git_dir.join("index"));
+    if let Some(branch) = git(dir, &["symbolic-ref", "-q", "HEAD"]) {
+        watched.push(git_dir.join(branch));
+    }
+
+    Some(Provenance { revision, dirty, watched })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn unstaged_edits_make_the_checkout_dirty() {
+        let dir = env::temp_dir().join(format!("provenance-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        let file = dir.join("src").join("main.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+
+        assert_eq!(provenance(&dir), None);
+        for args in [
+            &["init", "-q"][..],
+            &["add", "."],
+            &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "initial"],
+        ] {
+            git(&dir, args).unwrap();
+        }
+
+        let clean = provenance(&dir).unwrap();
+        assert_eq!(clean.revision.len(), 40);
+        assert!(!clean.dirty);
+        let file = fs::canonicalize(&file).unwrap();
+        assert!(clean.watched.iter().any(|w| fs::canonicalize(w).is_ok_and(|w| w == file)), "{:?}", clean.watched);
+
+        fs::write(&file, "fn main() { println!(); }\n").unwrap();
+        let edited = provenance(&dir).unwrap();
+        assert_eq!(edited.revision, clean.revision);
+        assert!(edited.dirty);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
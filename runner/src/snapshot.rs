@@ -0,0 +1,96 @@
+use crate::merge::merge;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+// how many differing lines a failed check shows
+const SHOWN: usize = 10;
+
+fn name(binary: &str) -> String {
+    Path::new(binary).file_stem().unwrap_or_default().to_string_lossy().to_string()
+}
+
+// runs an experiment binary with `args` into `out` and returns its results.csv
+fn run(binary: &str, args: &[&str], out: &Path) -> String {
+    let output = Command::new(binary)
+        .arg("--out")
+        .arg(out)
+        .args(["--format", "csv"])
+        .args(args)
+        .output()
+        .unwrap_or_else(|e| panic!("cannot run {}: {}", binary, e));
+    assert!(output.status.success(), "{} failed: {}", name(binary), String::from_utf8_lossy(&output.stderr));
+    fs::read_to_string(out.join("results.csv")).expect("the run wrote no results.csv")
+}
+
+fn scratch(binary: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("snapshot-{}-{}", name(binary), process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+// golden-output check for an experiment binary: runs it with `args` into a
+// scratch directory and compares its results.csv with the `snapshot` file.
+// With UPDATE_SNAPSHOTS=1 in the environment the snapshot is rewritten instead
+pub fn check(binary: &str, args: &[&str], snapshot: &Path) {
+    let out = scratch(binary);
+    let actual = run(binary, args, &out);
+    let _ = fs::remove_dir_all(&out);
+    compare(binary, actual, snapshot);
+}
+
+// the same check for a run split into `shards` with --shard and merged again,
+// which has to reproduce the unsharded results exactly
+pub fn check_sharded(binary: &str, args: &[&str], shards: usize, snapshot: &Path) {
+    let scratch = scratch(binary).with_extension("sharded");
+    let dirs = (1..=shards)
+        .map(|i| {
+            let dir = scratch.join(i.to_string());
+            let shard = format!("{}/{}", i, shards);
+            run(binary, &[args, &["--shard", &shard]].concat(), &dir);
+            dir
+        })
+        .collect::<Vec<_>>();
+    let out = scratch.join("merged");
+    let manifest = merge(&dirs, &out).unwrap_or_else(|e| panic!("cannot merge the shards: {}", e));
+    // the merged manifest still tells how each shard was run
+    for (i, args) in (1..=shards).zip(&manifest.shards) {
+        assert!(args.windows(2).any(|w| w == ["--shard", &format!("{}/{}", i, shards)]), "shard {} ran with {:?}", i, args);
+    }
+    assert_eq!(manifest.shards.len(), shards);
+    let actual = fs::read_to_string(out.join("results.csv")).unwrap();
+    let _ = fs::remove_dir_all(&scratch);
+    compare(binary, actual, snapshot);
+}
+
+fn compare(binary: &str, actual: String, snapshot: &Path) {
+    if env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v == "1") {
+        fs::create_dir_all(snapshot.parent().unwrap()).unwrap();
+        fs::write(snapshot, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(snapshot).unwrap_or_else(|_| {
+        panic!("no snapshot at {}, run with UPDATE_SNAPSHOTS=1 to create it", snapshot.display())
+    });
+    if actual == expected {
+        return;
+    }
+
+    let (expected, actual) = (expected.lines().collect::<Vec<_>>(), actual.lines().collect::<Vec<_>>());
+    let mut diff = Vec::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(i), actual.get(i));
+        if e != a {
+            diff.push(format!("line {}:\n  - {}\n  + {}", i + 1, e.unwrap_or(&"(missing)"), a.unwrap_or(&"(missing)")));
+        }
+    }
+    let more = diff.len().saturating_sub(SHOWN);
+    diff.truncate(SHOWN);
+    panic!(
+        "{} differs from {} in {} lines (rerun with UPDATE_SNAPSHOTS=1 to accept):\n{}{}",
+        name(binary), snapshot.display(), diff.len() + more, diff.join("\n"),
+        if more > 0 { format!("\n  ... and {} more", more) } else { String::new() }
+    );
+}
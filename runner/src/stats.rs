@@ -0,0 +1,511 @@
+// two-sided 95% quantiles of Student's t for 1..=30 degrees of freedom
+const T_975: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+const Z_975: f64 = 1.959963984540054;
+
+pub fn t_975(df: usize) -> f64 {
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => T_975[df - 1],
+        _ => {
+            // Cornish-Fisher expansion around the normal quantile
+            let z = Z_975;
+            let df = df as f64;
+            z + (z.powi(3) + z) / (4.0 * df)
+                + (5.0 * z.powi(5) + 16.0 * z.powi(3) + 3.0 * z) / (96.0 * df * df)
+        }
+    }
+}
+
+// Lanczos approximation (g = 7, n = 9)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// continued fraction for the incomplete beta function, evaluated with Lentz's method
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+// regularized incomplete beta function I_x(a, b)
+pub fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+// two-sided p-value of Student's t with (possibly fractional) df degrees of freedom
+pub fn t_two_sided(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TTest {
+    pub t: f64,
+    pub df: f64,
+    pub p: f64,
+}
+
+// Welch's unequal-variance t-test for the difference b - a of two means
+pub fn welch(a: &Summary, b: &Summary) -> Option<TTest> {
+    if a.reps < 2 || b.reps < 2 {
+        return None;
+    }
+
+    let va = a.variance / a.reps as f64;
+    let vb = b.variance / b.reps as f64;
+    let diff = b.mean - a.mean;
+    if va + vb == 0.0 {
+        // both samples are constant, so any difference is certain
+        let (t, p) = if diff == 0.0 { (0.0, 1.0) } else { (diff.signum() * f64::INFINITY, 0.0) };
+        return Some(TTest { t, df: f64::INFINITY, p });
+    }
+
+    let t = diff / (va + vb).sqrt();
+    let df = (va + vb).powi(2)
+        / (va * va / (a.reps - 1) as f64 + vb * vb / (b.reps - 1) as f64);
+    Some(TTest { t, df, p: t_two_sided(t, df) })
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    pub fn std_err(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.variance() / self.count as f64).sqrt()
+        }
+    }
+
+    pub fn half_width(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            t_975(self.count - 1) * self.std_err()
+        }
+    }
+}
+
+// P² estimator (Jain & Chlamtac): tracks a quantile with five markers
+#[derive(Debug, Clone, Copy)]
+pub struct P2Quantile {
+    p: f64,
+    count: usize,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    pub fn new(p: f64) -> Self {
+        Self {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+        self.count += 1;
+
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            (1..5).find(|&i| x < self.heights[i]).unwrap() - 1
+        };
+
+        for position in self.positions[(k + 1)..].iter_mut() {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            if (d >= 1.0 && self.positions[i + 1] - self.positions[i] > 1.0)
+                || (d <= -1.0 && self.positions[i - 1] - self.positions[i] < -1.0)
+            {
+                let s = d.signum();
+                let height = self.parabolic(i, s);
+                self.heights[i] = if self.heights[i - 1] < height && height < self.heights[i + 1] {
+                    height
+                } else {
+                    self.linear(i, s)
+                };
+                self.positions[i] += s;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, s: f64) -> f64 {
+        let (h, n) = (&self.heights, &self.positions);
+        h[i] + s / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + s) * (h[i + 1] - h[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - s) * (h[i] - h[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, s: f64) -> f64 {
+        let j = if s > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i] + s * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    pub fn quantile(&self) -> f64 {
+        match self.count {
+            0 => f64::NAN,
+            1..=4 => {
+                let mut seen = self.heights[..self.count].to_vec();
+                seen.sort_by(|a, b| a.total_cmp(b));
+                seen[((self.count - 1) as f64 * self.p).round() as usize]
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub reps: usize,
+    pub mean: f64,
+    pub variance: f64,
+    pub std_err: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    pub median: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepStats {
+    moments: Welford,
+    median: P2Quantile,
+    p95: P2Quantile,
+}
+
+impl StepStats {
+    pub fn new() -> Self {
+        Self {
+            moments: Welford::new(),
+            median: P2Quantile::new(0.5),
+            p95: P2Quantile::new(0.95),
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.moments.push(x);
+        self.median.push(x);
+        self.p95.push(x);
+    }
+
+    pub fn summary(&self) -> Summary {
+        let half_width = self.moments.half_width();
+        Summary {
+            reps: self.moments.count(),
+            mean: self.moments.mean(),
+            variance: self.moments.variance(),
+            std_err: self.moments.std_err(),
+            ci_low: self.moments.mean() - half_width,
+            ci_high: self.moments.mean() + half_width,
+            median: self.median.quantile(),
+            p95: self.p95.quantile(),
+        }
+    }
+}
+
+impl Default for StepStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// summaries of a curve labelled with their 1-based step
+pub type Samples = Vec<(usize, Summary)>;
+
+// per-step statistics of a prefix-cost curve across repetitions, kept only at
+// the sampled steps
+pub struct Curve {
+    steps: Vec<usize>,
+    stats: Vec<StepStats>,
+}
+
+impl Curve {
+    pub fn new(len: usize) -> Self {
+        Self::sampled((0..len).collect())
+    }
+
+    // `steps` must be increasing
+    pub fn sampled(steps: Vec<usize>) -> Self {
+        Self {
+            stats: vec![StepStats::new(); steps.len()],
+            steps,
+        }
+    }
+
+    pub fn push(&mut self, step: usize, x: f64) {
+        if let Ok(i) = self.steps.binary_search(&step) {
+            self.stats[i].push(x);
+        }
+    }
+
+    // 95% CI half-width of the last step relative to its mean
+    pub fn relative_half_width(&self) -> f64 {
+        let Some(last) = self.stats.last() else {
+            return 0.0;
+        };
+        let half_width = last.moments.half_width();
+        if half_width == 0.0 {
+            0.0
+        } else {
+            half_width / last.moments.mean().abs()
+        }
+    }
+
+    pub fn summary(&self) -> Samples {
+        self.steps.iter().zip(&self.stats).map(|(step, s)| (step + 1, s.summary())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn sample(len: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..len).map(|_| rng.gen_range(0.0..1000.0)).collect()
+    }
+
+    fn close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance * expected.abs().max(1e-300), "{} vs {}", actual, expected);
+    }
+
+    // reference values computed to 30 digits with mpmath's betainc
+    #[test]
+    fn incomplete_beta_matches_reference() {
+        close(incomplete_beta(2.0, 3.0, 0.5), 0.6875, 1e-12);
+        close(incomplete_beta(1.0, 1.0, 0.25), 0.25, 1e-12);
+        close(incomplete_beta(0.5, 0.5, 0.3), 0.36901011956554536, 1e-10);
+        close(incomplete_beta(10.0, 20.0, 0.4), 0.7853183897628262, 1e-10);
+        close(incomplete_beta(50.5, 0.5, 0.97), 0.08018110630331778, 1e-10);
+        assert_eq!(incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(incomplete_beta(2.0, 3.0, 1.0), 1.0);
+    }
+
+    // 2 * scipy.stats.t.sf(|t|, df)
+    #[test]
+    fn t_two_sided_matches_reference() {
+        close(t_two_sided(2.0, 10.0), 0.07338803477074037, 1e-10);
+        close(t_two_sided(-2.0, 10.0), 0.07338803477074037, 1e-10);
+        close(t_two_sided(2.228, 10.0), 0.05001177181711137, 1e-10);
+        close(t_two_sided(1.0, 1.0), 0.5, 1e-10);
+        close(t_two_sided(3.5, 4.5), 0.020541689969385574, 1e-10);
+        close(t_two_sided(10.0, 30.0), 4.575251408229613e-11, 1e-8);
+        close(t_two_sided(0.0, 5.0), 1.0, 1e-12);
+    }
+
+    fn summary(xs: &[f64]) -> Summary {
+        let mut stats = StepStats::new();
+        xs.iter().for_each(|&x| stats.push(x));
+        stats.summary()
+    }
+
+    // scipy.stats.ttest_ind(b, a, equal_var=False)
+    #[test]
+    fn welch_matches_reference() {
+        let a = summary(&[27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7, 21.4]);
+        let b = summary(&[27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5, 24.4]);
+        let test = welch(&a, &b).unwrap();
+        close(test.t, 2.4553563982860043, 1e-10);
+        close(test.df, 24.988529290231416, 1e-10);
+        close(test.p, 0.02137800146286706, 1e-9);
+
+        let test = welch(&summary(&[1.0, 2.0, 3.0, 4.0]), &summary(&[5.0, 6.0, 7.0, 8.0, 9.0, 10.0])).unwrap();
+        close(test.t, 5.0, 1e-12);
+        close(test.df, 7.9411764705882355, 1e-12);
+        close(test.p, 0.0010763172687101815, 1e-9);
+    }
+
+    #[test]
+    fn welch_on_degenerate_samples() {
+        assert!(welch(&summary(&[1.0]), &summary(&[1.0, 2.0])).is_none());
+        let same = welch(&summary(&[3.0, 3.0]), &summary(&[3.0, 3.0, 3.0])).unwrap();
+        assert_eq!((same.t, same.p), (0.0, 1.0));
+        let apart = welch(&summary(&[3.0, 3.0]), &summary(&[2.0, 2.0])).unwrap();
+        assert_eq!((apart.t, apart.p), (f64::NEG_INFINITY, 0.0));
+    }
+
+    #[test]
+    fn welford_matches_two_passes() {
+        for len in [1, 2, 3, 10, 1000] {
+            // a large offset makes a naive sum of squares lose precision
+            let xs = sample(len).into_iter().map(|x| x + 1e9).collect::<Vec<_>>();
+            let mut moments = Welford::new();
+            xs.iter().for_each(|&x| moments.push(x));
+
+            let mean = xs.iter().sum::<f64>() / len as f64;
+            let variance = if len < 2 { 0.0 } else { xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (len - 1) as f64 };
+            assert_eq!(moments.count(), len);
+            assert!((moments.mean() - mean).abs() < 1e-6, "mean {} vs {}", moments.mean(), mean);
+            assert!((moments.variance() - variance).abs() <= 1e-6 * variance.max(1.0), "variance {} vs {}", moments.variance(), variance);
+            assert!((moments.std_err() - (variance / len as f64).sqrt()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn half_width_uses_students_t() {
+        let mut moments = Welford::new();
+        [1.0, 2.0, 3.0, 4.0].iter().for_each(|&x| moments.push(x));
+        // s = sqrt(5/3), t(3) = 3.182
+        assert!((moments.half_width() - 3.182 * (5.0f64 / 3.0 / 4.0).sqrt()).abs() < 1e-12);
+        assert_eq!(Welford::new().half_width(), 0.0);
+    }
+
+    // the exact quantile as the P² estimator defines it, on the sorted sample
+    fn exact(xs: &[f64], p: f64) -> f64 {
+        let mut sorted = xs.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        sorted[((xs.len() - 1) as f64 * p).round() as usize]
+    }
+
+    #[test]
+    fn p2_is_exact_on_few_values() {
+        let xs = [5.0, 1.0, 4.0, 2.0];
+        for len in 1..=xs.len() {
+            for p in [0.5, 0.95] {
+                let mut quantile = P2Quantile::new(p);
+                xs[..len].iter().for_each(|&x| quantile.push(x));
+                assert_eq!(quantile.quantile(), exact(&xs[..len], p), "{} values, p = {}", len, p);
+            }
+        }
+        assert!(P2Quantile::new(0.5).quantile().is_nan());
+    }
+
+    #[test]
+    fn p2_tracks_exact_quantiles() {
+        let xs = sample(10000);
+        for p in [0.5, 0.95] {
+            let mut quantile = P2Quantile::new(p);
+            xs.iter().for_each(|&x| quantile.push(x));
+            let (estimate, exact) = (quantile.quantile(), exact(&xs, p));
+            // within half a percent of the range of the sample
+            assert!((estimate - exact).abs() < 5.0, "p = {}: estimated {}, exact {}", p, estimate, exact);
+        }
+
+        // a skewed sample, with the values in increasing order
+        let xs = (1..=1000).map(|i| (i as f64).powi(2)).collect::<Vec<_>>();
+        for p in [0.5, 0.95] {
+            let mut quantile = P2Quantile::new(p);
+            xs.iter().for_each(|&x| quantile.push(x));
+            let (estimate, exact) = (quantile.quantile(), exact(&xs, p));
+            assert!((estimate - exact).abs() / exact < 0.02, "p = {}: estimated {}, exact {}", p, estimate, exact);
+        }
+    }
+}
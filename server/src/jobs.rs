@@ -0,0 +1,524 @@
+use runner::config::Config;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+// the experiment binaries a job may run
+pub const BINARIES: [&str; 5] = ["list1", "list2", "list3", "list4", "list5"];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Status {
+    Queued, Running, Done, Failed, Cancelled,
+}
+
+impl Status {
+    fn finished(&self) -> bool {
+        matches!(self, Status::Done | Status::Failed | Status::Cancelled)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Queued => "queued",
+            Status::Running => "running",
+            Status::Done => "done",
+            Status::Failed => "failed",
+            Status::Cancelled => "cancelled",
+        }
+    }
+}
+
+fn scalar(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        other => Err(format!("expected a string or a number, got {}", other)),
+    }
+}
+
+// an experiment binary and the runner arguments to run it with, given as
+// {"binary": "list1", "seed": 1, "sample": "every:50", "set": {"reps": 3}};
+// every other field becomes the runner option of the same name
+pub struct Spec {
+    request: Value,
+    binary: String,
+    args: Vec<String>,
+}
+
+impl Spec {
+    pub fn parse(request: Value) -> Result<Self, String> {
+        let Value::Object(fields) = &request else {
+            return Err("expected a JSON object".to_string());
+        };
+        let binary = match fields.get("binary") {
+            Some(Value::String(binary)) if BINARIES.contains(&binary.as_str()) => binary.clone(),
+            _ => return Err(format!("binary must be one of {}", BINARIES.join(", "))),
+        };
+
+        let mut args = Vec::new();
+        for (name, value) in fields {
+            match (name.as_str(), value) {
+                ("binary", _) => {}
+                ("out" | "format" | "resume", _) => return Err(format!("{} is chosen by the server", name)),
+                ("set", Value::Object(settings)) => {
+                    for (setting, value) in settings {
+                        args.push("--set".to_string());
+                        args.push(format!("{}={}", setting, scalar(value)?));
+                    }
+                }
+                (_, value) => {
+                    args.push(format!("--{}", name.replace('_', "-")));
+                    args.push(scalar(value)?);
+                }
+            }
+        }
+        // unknown options and bad values are rejected now rather than when the job runs
+        Config::parse(args.clone()).map_err(|e| e.to_string())?;
+
+        Ok(Self { request, binary, args })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub kind: &'static str,
+    pub data: Value,
+}
+
+impl Event {
+    fn new(kind: &'static str, data: Value) -> Self {
+        Self { kind, data }
+    }
+
+    // the runner logs one line per finished cell when its output is not a
+    // terminal, starting with [cell/cells]
+    fn progress(line: &str) -> Self {
+        let counts = line
+            .strip_prefix('[')
+            .and_then(|l| l.split_once(']'))
+            .and_then(|(counts, _)| counts.split_once('/'))
+            .and_then(|(cell, cells)| Some((cell.parse::<usize>().ok()?, cells.parse::<usize>().ok()?)));
+        match counts {
+            Some((cell, cells)) => Self::new("progress", json!({ "cell": cell, "cells": cells, "status": line })),
+            None => Self::new("progress", json!({ "status": line })),
+        }
+    }
+}
+
+// the rows appended to a results.jsonl since the last read
+struct Tail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl Tail {
+    fn read(&mut self) -> Vec<Value> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let mut text = String::new();
+        if file.seek(SeekFrom::Start(self.offset)).and_then(|_| file.read_to_string(&mut text)).is_err() {
+            return Vec::new();
+        }
+
+        // a row still being written is left for the next read
+        let complete = text.rfind('\n').map_or(0, |i| i + 1);
+        self.offset += complete as u64;
+        text[..complete].lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+    }
+}
+
+struct Job {
+    spec: Spec,
+    status: Status,
+    events: Vec<Event>,
+    child: Option<Child>,
+}
+
+impl Job {
+    // a cancelled job is finished as soon as it is cancelled, but its stream
+    // goes on until the worker has stopped it
+    fn ended(&self) -> bool {
+        self.events.last().is_some_and(|event| event.kind == "finished")
+    }
+}
+
+struct State {
+    jobs: Vec<Job>,
+    pending: VecDeque<usize>,
+}
+
+// jobs run in submission order, each into its own directory; every change
+// to a job wakes all the threads waiting on `changed`
+pub struct Queue {
+    dir: PathBuf,
+    bin_dir: PathBuf,
+    // ids continue after the directories of earlier runs of the server
+    first: usize,
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Queue {
+    pub fn new(dir: PathBuf, bin_dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let first = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<usize>().ok())
+            .max()
+            .map_or(1, |id| id + 1);
+
+        Ok(Self {
+            dir,
+            bin_dir,
+            first,
+            state: Mutex::new(State { jobs: Vec::new(), pending: VecDeque::new() }),
+            changed: Condvar::new(),
+        })
+    }
+
+    fn index(&self, state: &State, id: usize) -> Option<usize> {
+        id.checked_sub(self.first).filter(|&i| i < state.jobs.len())
+    }
+
+    fn out(&self, id: usize) -> PathBuf {
+        self.dir.join(id.to_string())
+    }
+
+    pub fn submit(&self, spec: Spec) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let id = self.first + state.jobs.len();
+        state.jobs.push(Job {
+            spec,
+            status: Status::Queued,
+            events: vec![Event::new("queued", json!({ "id": id }))],
+            child: None,
+        });
+        state.pending.push_back(id);
+        self.changed.notify_all();
+        id
+    }
+
+    fn describe(&self, id: usize, job: &Job) -> Value {
+        json!({
+            "id": id,
+            "status": job.status.name(),
+            "spec": job.spec.request,
+            "out": self.out(id),
+            "events": job.events.len(),
+        })
+    }
+
+    pub fn list(&self) -> Value {
+        let state = self.state.lock().unwrap();
+        state.jobs.iter().enumerate().map(|(i, job)| self.describe(self.first + i, job)).collect()
+    }
+
+    pub fn get(&self, id: usize) -> Option<Value> {
+        let state = self.state.lock().unwrap();
+        self.index(&state, id).map(|i| self.describe(id, &state.jobs[i]))
+    }
+
+    // None for an unknown job, Some(false) for one that has already finished
+    pub fn cancel(&self, id: usize) -> Option<bool> {
+        let mut state = self.state.lock().unwrap();
+        let i = self.index(&state, id)?;
+        if state.jobs[i].status.finished() {
+            return Some(false);
+        }
+
+        state.pending.retain(|&pending| pending != id);
+        let job = &mut state.jobs[i];
+        let queued = job.status == Status::Queued;
+        job.status = Status::Cancelled;
+        if queued {
+            job.events.push(Event::new("finished", json!({ "status": "cancelled" })));
+        } else if let Some(child) = &mut job.child {
+            // the worker notices the exit and reports it
+            let _ = child.kill();
+        }
+        self.changed.notify_all();
+        Some(true)
+    }
+
+    // calls `send` with the events of a job from index `from` on, waiting for
+    // new ones until the job finishes
+    pub fn follow<F>(&self, id: usize, from: usize, mut send: F) -> io::Result<()>
+    where F: FnMut(usize, &Event) -> io::Result<()>
+    {
+        let mut next = from;
+        loop {
+            let (events, finished) = {
+                let mut state = self.state.lock().unwrap();
+                let Some(i) = self.index(&state, id) else {
+                    return Ok(());
+                };
+                while state.jobs[i].events.len() <= next && !state.jobs[i].ended() {
+                    state = self.changed.wait(state).unwrap();
+                }
+                let job = &state.jobs[i];
+                (job.events.get(next..).unwrap_or_default().to_vec(), job.ended())
+            };
+
+            for event in events {
+                send(next, &event)?;
+                next += 1;
+            }
+            if finished {
+                return Ok(());
+            }
+        }
+    }
+
+    fn push(&self, id: usize, events: Vec<Event>) {
+        let mut state = self.state.lock().unwrap();
+        let i = self.index(&state, id).unwrap();
+        state.jobs[i].events.extend(events);
+        self.changed.notify_all();
+    }
+
+    fn finish(&self, id: usize, status: Status, error: Option<String>) {
+        let mut state = self.state.lock().unwrap();
+        let i = self.index(&state, id).unwrap();
+        let job = &mut state.jobs[i];
+        // a cancelled job stays cancelled however its process ended
+        if job.status != Status::Cancelled {
+            job.status = status;
+        }
+        let mut finished = json!({ "status": job.status.name() });
+        if let Some(error) = error.filter(|_| job.status == Status::Failed) {
+            finished["error"] = json!(error);
+        }
+        job.events.push(Event::new("finished", finished));
+        self.changed.notify_all();
+    }
+
+    // runs queued jobs one after another, forever
+    pub fn work(&self) {
+        loop {
+            let (id, binary, args) = {
+                let mut state = self.state.lock().unwrap();
+                let id = loop {
+                    match state.pending.pop_front() {
+                        Some(id) => break id,
+                        None => state = self.changed.wait(state).unwrap(),
+                    }
+                };
+                let i = self.index(&state, id).unwrap();
+                let job = &mut state.jobs[i];
+                job.status = Status::Running;
+                (id, job.spec.binary.clone(), job.spec.args.clone())
+            };
+            self.run(id, &self.bin_dir.join(format!("{}{}", binary, env::consts::EXE_SUFFIX)), &args);
+        }
+    }
+
+    fn run(&self, id: usize, binary: &Path, args: &[String]) {
+        let out = self.out(id);
+        let spawned = Command::new(binary)
+            .arg("--out")
+            .arg(&out)
+            .args(["--format", "both"])
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => return self.finish(id, Status::Failed, Some(format!("cannot run {}: {}", binary.display(), e))),
+        };
+
+        let stdout = child.stdout.take().unwrap();
+        let mut stderr = child.stderr.take().unwrap();
+        let errors = thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        });
+        {
+            let mut state = self.state.lock().unwrap();
+            let i = self.index(&state, id).unwrap();
+            let job = &mut state.jobs[i];
+            // cancelled between leaving the queue and starting
+            if job.status == Status::Cancelled {
+                let _ = child.kill();
+            }
+            job.child = Some(child);
+            job.events.push(Event::new("started", json!({ "out": out })));
+            self.changed.notify_all();
+        }
+
+        // the results of a cell are flushed before its progress line is printed
+        let mut results = Tail { path: out.join("results.jsonl"), offset: 0 };
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let mut events = vec![Event::progress(&line)];
+            events.extend(results.read().into_iter().map(|row| Event::new("result", row)));
+            self.push(id, events);
+        }
+        self.push(id, results.read().into_iter().map(|row| Event::new("result", row)).collect());
+
+        let child = {
+            let mut state = self.state.lock().unwrap();
+            let i = self.index(&state, id).unwrap();
+            state.jobs[i].child.take()
+        };
+        let exit = child.map(|mut child| child.wait());
+        let errors = errors.join().unwrap_or_default();
+        match exit {
+            Some(Ok(exit)) if exit.success() => self.finish(id, Status::Done, None),
+            Some(Ok(exit)) => {
+                let error = if errors.trim().is_empty() { exit.to_string() } else { errors.trim().to_string() };
+                self.finish(id, Status::Failed, Some(error))
+            }
+            Some(Err(e)) => self.finish(id, Status::Failed, Some(e.to_string())),
+            None => self.finish(id, Status::Failed, None),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::process;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    // stands in for list1, called as `list1 --out DIR --format both --seed N`:
+    // seed 1 hangs until it is killed, any other seed logs itself next to the
+    // job directories and finishes two cells
+    const LIST1: &str = r#"#!/bin/sh
+mkdir -p "$2"
+if [ "$6" = 1 ]; then
+    exec sleep 60
+fi
+echo "$6" >> "$2/../order"
+echo '{"cell":1}' >> "$2/results.jsonl"
+echo "[1/2] first"
+echo "[2/2] second"
+"#;
+
+    fn queue(name: &str) -> (Arc<Queue>, PathBuf) {
+        let dir = env::temp_dir().join(format!("server-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let bin_dir = dir.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let list1 = bin_dir.join("list1");
+        fs::write(&list1, LIST1).unwrap();
+        fs::set_permissions(&list1, fs::Permissions::from_mode(0o755)).unwrap();
+        (Arc::new(Queue::new(dir.join("jobs"), bin_dir).unwrap()), dir)
+    }
+
+    fn worker(queue: &Arc<Queue>) {
+        let queue = queue.clone();
+        thread::spawn(move || queue.work());
+    }
+
+    fn spec(seed: u64) -> Spec {
+        Spec::parse(json!({ "binary": "list1", "seed": seed })).unwrap()
+    }
+
+    fn followed(queue: &Queue, id: usize, from: usize) -> Vec<(usize, &'static str, Value)> {
+        let mut events = Vec::new();
+        queue
+            .follow(id, from, |index, event| {
+                events.push((index, event.kind, event.data.clone()));
+                Ok(())
+            })
+            .unwrap();
+        events
+    }
+
+    fn kinds(events: &[(usize, &'static str, Value)]) -> Vec<&'static str> {
+        events.iter().map(|(_, kind, _)| *kind).collect()
+    }
+
+    #[test]
+    fn jobs_run_in_submission_order() {
+        let (queue, dir) = queue("order");
+        let ids = [2, 3, 4].map(|seed| queue.submit(spec(seed)));
+        assert_eq!(ids, [1, 2, 3]);
+        worker(&queue);
+
+        for id in ids {
+            let events = followed(&queue, id, 0);
+            assert_eq!(kinds(&events), ["queued", "started", "progress", "result", "progress", "finished"]);
+            assert_eq!(events[2].2["cell"], 1);
+            assert_eq!(events[3].2, json!({ "cell": 1 }));
+            assert_eq!(events[5].2, json!({ "status": "done" }));
+            assert_eq!(queue.get(id).unwrap()["status"], "done");
+        }
+        assert_eq!(fs::read_to_string(dir.join("jobs").join("order")).unwrap(), "2\n3\n4\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cancelling_a_queued_job_finishes_it() {
+        let (queue, dir) = queue("queued");
+        let id = queue.submit(spec(2));
+
+        assert_eq!(queue.cancel(id), Some(true));
+        assert_eq!(queue.get(id).unwrap()["status"], "cancelled");
+        let events = followed(&queue, id, 0);
+        assert_eq!(kinds(&events), ["queued", "finished"]);
+        assert_eq!(events[1].2, json!({ "status": "cancelled" }));
+
+        assert_eq!(queue.cancel(id), Some(false));
+        assert_eq!(queue.cancel(id + 1), None);
+
+        // a worker started afterwards never runs it
+        let next = queue.submit(spec(3));
+        worker(&queue);
+        followed(&queue, next, 0);
+        assert_eq!(fs::read_to_string(dir.join("jobs").join("order")).unwrap(), "3\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cancelling_a_running_job_kills_its_process() {
+        let (queue, dir) = queue("running");
+        worker(&queue);
+        let id = queue.submit(spec(1));
+
+        // follow until the process has started, then stop following
+        let started = queue.follow(id, 0, |_, event| match event.kind {
+            "started" => Err(io::Error::other("started")),
+            _ => Ok(()),
+        });
+        assert!(started.is_err());
+        assert_eq!(queue.get(id).unwrap()["status"], "running");
+
+        let start = Instant::now();
+        assert_eq!(queue.cancel(id), Some(true));
+        let events = followed(&queue, id, 0);
+        assert!(start.elapsed() < Duration::from_secs(30));
+        assert_eq!(kinds(&events), ["queued", "started", "finished"]);
+        assert_eq!(events[2].2, json!({ "status": "cancelled" }));
+        assert_eq!(queue.get(id).unwrap()["status"], "cancelled");
+        assert_eq!(queue.cancel(id), Some(false));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn following_resumes_after_the_last_event_id() {
+        let (queue, dir) = queue("resume");
+        worker(&queue);
+        let id = queue.submit(spec(2));
+
+        let all = followed(&queue, id, 0);
+        assert_eq!(all.iter().map(|(index, _, _)| *index).collect::<Vec<_>>(), (0..all.len()).collect::<Vec<_>>());
+        // a client that saw event 2 asks for the ones after it
+        assert_eq!(followed(&queue, id, 3), all[3..]);
+        assert_eq!(followed(&queue, id, all.len()), []);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
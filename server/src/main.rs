@@ -0,0 +1,171 @@
+use jobs::{Event, Queue, Spec};
+use serde_json::{json, Value};
+use std::env;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+mod jobs;
+
+const USAGE: &str = "usage: server [--addr HOST:PORT] [--jobs DIR] [--bin-dir DIR] [--workers N]
+  POST   /jobs                          queue an experiment, e.g. {\"binary\": \"list1\", \"seed\": 1, \"set\": {\"reps\": 3}}
+  GET    /jobs                          list the jobs
+  GET    /jobs/ID                       show a job
+  GET    /jobs/ID/events                stream its progress and results as server-sent events
+  GET    /jobs/ID/events?format=jsonl   the same stream as chunked JSON lines
+  DELETE /jobs/ID                       cancel a queued or running job";
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
+
+// the dashboard is served from another localhost port
+fn respond(request: Request, status: u16, body: Value) {
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"));
+    let _ = request.respond(response);
+}
+
+fn error(request: Request, status: u16, message: String) {
+    respond(request, status, json!({ "error": message }));
+}
+
+// tiny_http buffers chunked bodies, so streams write their own response;
+// `from` is the index of the first event to send
+fn stream(request: Request, queue: &Queue, id: usize, jsonl: bool) -> io::Result<()> {
+    let from = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Last-Event-ID"))
+        .and_then(|h| h.value.as_str().parse::<usize>().ok())
+        .map_or(0, |last| last + 1);
+    let content_type = if jsonl { "application/x-ndjson" } else { "text/event-stream" };
+
+    let mut writer = request.into_writer();
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nCache-Control: no-cache\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n",
+        content_type
+    )?;
+    writer.flush()?;
+
+    queue.follow(id, from, |index, event: &Event| {
+        let frame = if jsonl {
+            format!("{}\n", json!({ "id": index, "event": event.kind, "data": event.data }))
+        } else {
+            format!("id: {}\nevent: {}\ndata: {}\n\n", index, event.kind, event.data)
+        };
+        write!(writer, "{:x}\r\n{}\r\n", frame.len(), frame)?;
+        writer.flush()
+    })?;
+    write!(writer, "0\r\n\r\n")?;
+    writer.flush()
+}
+
+fn handle(mut request: Request, queue: &Queue) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let id = segments.get(1).and_then(|id| id.parse::<usize>().ok());
+
+    match (request.method(), segments.as_slice(), id) {
+        (Method::Options, _, _) => {
+            let response = Response::empty(204)
+                .with_header(header("Access-Control-Allow-Origin", "*"))
+                .with_header(header("Access-Control-Allow-Methods", "GET, POST, DELETE"))
+                .with_header(header("Access-Control-Allow-Headers", "Content-Type, Last-Event-ID"));
+            let _ = request.respond(response);
+        }
+        (Method::Post, ["jobs"], _) => {
+            let mut body = String::new();
+            if let Err(e) = request.as_reader().read_to_string(&mut body) {
+                return error(request, 400, format!("cannot read the request: {}", e));
+            }
+            let spec = serde_json::from_str(&body).map_err(|e| format!("invalid JSON: {}", e)).and_then(Spec::parse);
+            match spec {
+                Ok(spec) => {
+                    let id = queue.submit(spec);
+                    respond(request, 201, json!({ "id": id }));
+                }
+                Err(e) => error(request, 400, e),
+            }
+        }
+        (Method::Get, ["jobs"], _) => respond(request, 200, queue.list()),
+        (Method::Get, ["jobs", _], Some(id)) => match queue.get(id) {
+            Some(job) => respond(request, 200, job),
+            None => error(request, 404, format!("no job {}", id)),
+        },
+        (Method::Get, ["jobs", _, "events"], Some(id)) => {
+            if queue.get(id).is_none() {
+                return error(request, 404, format!("no job {}", id));
+            }
+            // a client that went away shows up as a failed write
+            let _ = stream(request, queue, id, query.split('&').any(|q| q == "format=jsonl"));
+        }
+        (Method::Delete, ["jobs", _], Some(id)) => match queue.cancel(id) {
+            Some(true) => respond(request, 200, json!({ "id": id, "status": "cancelled" })),
+            Some(false) => error(request, 409, format!("job {} has already finished", id)),
+            None => error(request, 404, format!("no job {}", id)),
+        },
+        _ => {
+            let route = format!("no route for {} {}", request.method(), path);
+            error(request, 404, route)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut addr = "127.0.0.1:8080".to_string();
+    let mut dir = PathBuf::from("jobs");
+    // the experiment binaries are built next to this one
+    let mut bin_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_default();
+    let mut workers = 1;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| USAGE.to_string());
+        match arg.as_str() {
+            "--addr" => addr = value()?.clone(),
+            "--jobs" => dir = PathBuf::from(value()?),
+            "--bin-dir" => bin_dir = PathBuf::from(value()?),
+            "--workers" => {
+                let v = value()?;
+                workers = v.parse().ok().filter(|&n: &usize| n > 0).ok_or_else(|| format!("--workers expects a positive number, got {}", v))?;
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let queue = Arc::new(Queue::new(dir.clone(), bin_dir).map_err(|e| format!("cannot use {}: {}", dir.display(), e))?);
+    let server = Server::http(&addr).map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+    for _ in 0..workers {
+        let queue = queue.clone();
+        thread::spawn(move || queue.work());
+    }
+    println!("listening on http://{}, {} worker(s), jobs in {}", addr, workers, dir.display());
+
+    for request in server.incoming_requests() {
+        let queue = queue.clone();
+        thread::spawn(move || handle(request, &queue));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}